@@ -0,0 +1,146 @@
+// Compares the DNEC index, computing indexes from an order statistics tree, with a hash map storing them, on a
+// collection of 1M elements. Run with `cargo bench`.
+extern crate nec;
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use nec::nec::{NamedElementsCollection, DNEC};
+
+// DNEC storing the indexes of each name
+type VecDNEC<Element> =
+    NamedElementsCollection<Element, Vec<usize>, String, HashMap<String, Vec<usize>>>;
+
+const LEN: usize = 1_000_000;
+const NAMES: usize = 250_000;
+const OPS: usize = 100;
+const LOOKUPS: usize = 100_000;
+
+// common operations of both collections
+trait Bench {
+    fn name() -> &'static str;
+    fn new() -> Self;
+    fn push(&mut self, name: &str, elem: usize);
+    fn insert(&mut self, index: usize, name: &str, elem: usize);
+    fn remove(&mut self, index: usize);
+    fn remove_by_name(&mut self, name: &str);
+    fn lookup(&self, name: &str) -> usize;
+}
+
+macro_rules! impl_bench {
+    ($t:ty, $name:expr) => {
+        impl Bench for $t {
+            fn name() -> &'static str {
+                $name
+            }
+
+            fn new() -> Self {
+                <$t>::new()
+            }
+
+            fn push(&mut self, name: &str, elem: usize) {
+                <$t>::push(self, name, elem);
+            }
+
+            fn insert(&mut self, index: usize, name: &str, elem: usize) {
+                <$t>::insert(self, index, name, elem);
+            }
+
+            fn remove(&mut self, index: usize) {
+                <$t>::remove(self, index);
+            }
+
+            fn remove_by_name(&mut self, name: &str) {
+                self.remove_nth_by_name(name, 0).unwrap();
+            }
+
+            fn lookup(&self, name: &str) -> usize {
+                *self.first_by_name(name).unwrap()
+            }
+        }
+    };
+}
+
+impl_bench!(DNEC<usize>, "DNEC");
+impl_bench!(VecDNEC<usize>, "VecDNEC");
+
+fn name(i: usize) -> String {
+    format!("NAME{}", i % NAMES)
+}
+
+fn time<F: FnOnce()>(f: F) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn report(collection: &str, op: &str, count: usize, elapsed: Duration) {
+    println!(
+        "{:<12}{:<20}{:>12.3} ms{:>14.0} ns/op",
+        collection,
+        op,
+        elapsed.as_secs_f64() * 1e3,
+        elapsed.as_secs_f64() * 1e9 / count as f64
+    );
+}
+
+fn run<B: Bench>() {
+    let names: Vec<_> = (0..LEN).map(name).collect();
+    let mut c = B::new();
+
+    let elapsed = time(|| {
+        for (i, n) in names.iter().enumerate() {
+            c.push(n, i);
+        }
+    });
+    report(B::name(), "push", LEN, elapsed);
+
+    let mut sum = 0;
+    let elapsed = time(|| {
+        for n in names.iter().step_by(LEN / LOOKUPS) {
+            sum += c.lookup(n);
+        }
+    });
+    assert!(sum > 0);
+    report(B::name(), "lookup", LOOKUPS, elapsed);
+
+    let elapsed = time(|| {
+        for _ in 0..OPS {
+            c.remove(0);
+        }
+    });
+    report(B::name(), "remove front", OPS, elapsed);
+
+    let elapsed = time(|| {
+        for i in 0..OPS {
+            c.remove(LEN / 2 - i);
+        }
+    });
+    report(B::name(), "remove middle", OPS, elapsed);
+
+    let elapsed = time(|| {
+        for (i, n) in names.iter().enumerate().take(OPS) {
+            c.insert(0, n, i);
+        }
+    });
+    report(B::name(), "insert front", OPS, elapsed);
+
+    let elapsed = time(|| {
+        for (i, n) in names.iter().enumerate().take(OPS) {
+            c.insert(LEN / 2, n, i);
+        }
+    });
+    report(B::name(), "insert middle", OPS, elapsed);
+
+    let elapsed = time(|| {
+        for i in 0..OPS {
+            c.remove_by_name(&names[LEN / 2 + i]);
+        }
+    });
+    report(B::name(), "remove by name", OPS, elapsed);
+}
+
+fn main() {
+    run::<DNEC<usize>>();
+    run::<VecDNEC<usize>>();
+}
@@ -1,27 +1,15 @@
-use std::collections::HashMap;
-
-/// Trait that the underlying `hmap` field of the `NamedElementsCollection` struct must implement. Function
-/// names are self-explanatory.
-pub trait Adjustable {
-    fn add_element(&mut self, name: &str, index: usize);
-    fn delete_element(&mut self, name: &str, index: usize);
-    fn replace_element(&mut self, name: &str, index: usize);
-    //fn insert_element(&mut self, name: &str, index: usize);
-    fn already_in(&mut self, name: &str) -> Option<usize>;
-}
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
 
-/// Case of non-duplicated elements.
-///
-/// Example:
-///
-/// list -> [("Elem1", e1), ("Elem4", e4), ("Elem3", e3), ("Elem2", e2)]
-/// hash -> {("Elem1", 0), ("Elem2", 3), ("Elem3", 2), ("Elem4", 1)}
-///
-impl Adjustable for HashMap<String, usize> {
+/// Trait that the underlying `hmap` field of the `NamedElementsCollection` struct must implement, for names of
+/// type `K`. Function names are self-explanatory.
+pub trait Adjustable<K> {
+    /// Adds the `index` entry for `name`, for an element added without moving any other.
+    ///
     /// # Examples
     ///
     /// ```
-    /// use std::collections::HashMap;
+    /// use std::collections::{BTreeMap, HashMap};
     /// use nec::adjustable::Adjustable;
     ///
     /// let mut h: HashMap<String, usize> = HashMap::new();
@@ -32,33 +20,38 @@ impl Adjustable for HashMap<String, usize> {
     ///
     /// assert_eq!(h.keys().len(), 10);
     /// ```
-    fn add_element(&mut self, name: &str, index: usize) {
-        self.insert(name.to_string(), index);
-    }
-
-    /// # Examples
     ///
     /// ```
-    /// use std::collections::HashMap;
+    /// use std::collections::{BTreeMap, HashMap};
     /// use nec::adjustable::Adjustable;
     ///
-    /// let mut h: HashMap<String, usize> = HashMap::new();
+    /// let mut h: HashMap<String, Vec<usize>> = HashMap::new();
     ///
-    /// for i in 0..10 {
-    ///     h.add_element(&format!("NAME{}",i), i);
+    /// for j in 0..3 {
+    ///     for i in 0..3 {
+    ///         h.add_element(&format!("NAME{}",i), i);
+    ///     }
     /// }
     ///
-    /// h.delete_element("NAME5", 5);
-    /// assert_eq!(h.keys().len(), 9);
+    /// assert_eq!(h.keys().len(), 3);
+    /// assert_eq!(h.get("NAME0").unwrap(), &vec![0,0,0]);
+    /// assert_eq!(h.get("NAME1").unwrap(), &vec![1,1,1]);
+    /// assert_eq!(h.get("NAME2").unwrap(), &vec![2,2,2]);
+    ///
+    /// // indexes are kept sorted
+    /// h.add_element(&"NAME3".to_string(), 5);
+    /// h.add_element(&"NAME3".to_string(), 2);
+    /// assert_eq!(h.get("NAME3").unwrap(), &vec![2,5]);
     /// ```
-    fn delete_element(&mut self, name: &str, _index: usize) {
-        self.remove(name);
-    }
+    fn add_element(&mut self, name: &K, index: usize);
 
+    /// Removes the `index` entry for `name`, and shifts down the indexes above it, for an element removed from
+    /// the list.
+    ///
     /// # Examples
     ///
     /// ```
-    /// use std::collections::HashMap;
+    /// use std::collections::{BTreeMap, HashMap};
     /// use nec::adjustable::Adjustable;
     ///
     /// let mut h: HashMap<String, usize> = HashMap::new();
@@ -67,20 +60,71 @@ impl Adjustable for HashMap<String, usize> {
     ///     h.add_element(&format!("NAME{}",i), i);
     /// }
     ///
-    /// h.delete_element("NAME5", 5);
+    /// h.delete_element(&"NAME5".to_string(), 5);
     /// assert_eq!(h.keys().len(), 9);
+    /// assert_eq!(h.get("NAME4").unwrap(), &4);
+    /// assert_eq!(h.get("NAME6").unwrap(), &5);
+    /// assert_eq!(h.get("NAME9").unwrap(), &8);
     /// ```
-    // fn insert_element(&mut self, name: &str, index: usize) {
-    //     for i in index+1 .. self.len() {
-
-    //     }
-    //     self.remove(name);
-    // }
+    ///
+    /// ```
+    /// use std::collections::{BTreeMap, HashMap};
+    /// use nec::adjustable::Adjustable;
+    ///
+    /// let mut h: HashMap<String, Vec<usize>> = HashMap::new();
+    ///
+    /// // set a specific data set for testing:
+    /// // 0 1 2 3 4 5 6 7 8 9
+    /// // A B A A B A A B B A
+    ///
+    /// h.insert("A".to_string(), vec![0,2,3,5,6,9]);
+    /// h.insert("B".to_string(), vec![1,4,7,8]);
+    ///
+    /// h.delete_element(&"A".to_string(),0);
+    /// assert_eq!(h.get("A").unwrap(), &vec![1,2,4,5,8]);
+    /// assert_eq!(h.get("B").unwrap(), &vec![0,3,6,7]);
+    ///
+    /// h.delete_element(&"A".to_string(),2);
+    /// assert_eq!(h.get("A").unwrap(), &vec![1,3,4,7]);
+    /// assert_eq!(h.get("B").unwrap(), &vec![0,2,5,6]);
+    ///
+    /// h.delete_element(&"A".to_string(),7);
+    /// assert_eq!(h.get("A").unwrap(), &vec![1,3,4]);
+    /// assert_eq!(h.get("B").unwrap(), &vec![0,2,5,6]);
+    ///
+    /// h.delete_element(&"A".to_string(),3);
+    /// assert_eq!(h.get("A").unwrap(), &vec![1,3]);
+    /// assert_eq!(h.get("B").unwrap(), &vec![0,2,4,5]);
+    ///
+    /// h.delete_element(&"A".to_string(),1);
+    /// assert_eq!(h.get("A").unwrap(), &vec![2]);
+    /// assert_eq!(h.get("B").unwrap(), &vec![0,1,3,4]);
+    ///
+    /// h.delete_element(&"A".to_string(),2);
+    /// assert!(!h.contains_key("A"));
+    /// assert_eq!(h.get("B").unwrap(), &vec![0,1,2,3]);
+    ///
+    /// h.delete_element(&"B".to_string(),0);
+    /// assert_eq!(h.get("B").unwrap(), &vec![0,1,2]);
+    ///
+    /// h.delete_element(&"B".to_string(),2);
+    /// assert_eq!(h.get("B").unwrap(), &vec![0,1]);
+    ///
+    /// h.delete_element(&"B".to_string(),0);
+    /// assert_eq!(h.get("B").unwrap(), &vec![0]);
+    ///
+    /// h.delete_element(&"B".to_string(),0);
+    /// assert!(!h.contains_key("B"));
+    /// ```
+    fn delete_element(&mut self, name: &K, index: usize);
 
+    /// Sets `index` as the entry for `name`, for an element replaced in place. Maps of duplicated names are
+    /// left unchanged.
+    ///
     /// # Examples
     ///
     /// ```
-    /// use std::collections::HashMap;
+    /// use std::collections::{BTreeMap, HashMap};
     /// use nec::adjustable::Adjustable;
     ///
     /// let mut h: HashMap<String, usize> = HashMap::new();
@@ -96,129 +140,294 @@ impl Adjustable for HashMap<String, usize> {
     /// assert_eq!(h.keys().len(), 10);
     /// assert_eq!(h.get("NAME0").unwrap(), &1);
     /// ```
-    fn replace_element(&mut self, name: &str, index: usize) {
-        self.insert(name.to_string(), index);
-    }
-
-    fn already_in(&mut self, name: &str) -> Option<usize> {
-        if self.contains_key(name) {
-            let index = *self.get(name).unwrap();
-            Some(index)
-        } else {
-            None
-        }
-    }
-}
+    fn replace_element(&mut self, name: &K, index: usize);
 
-/// Case of duplicated elements possible. This case is much more complicated, because indexes are
-/// stored in a vector.
-///
-/// Example:
-///
-/// list -> ("Elem1", e1), ("Elem2, e2"), ("Elem3, e3"), ("Elem1", e1), ("Elem2", e2)
-/// hash -> ("Elem1", [0,3]), ("Elem2", [1,4]), ("Elem3", [2])
-///
-impl Adjustable for HashMap<String, Vec<usize>> {
+    /// Adds the `index` entry for `name`, and shifts up the indexes from it, for an element inserted into the
+    /// list.
+    ///
     /// # Examples
     ///
     /// ```
-    /// use std::collections::HashMap;
+    /// use std::collections::{BTreeMap, HashMap};
     /// use nec::adjustable::Adjustable;
     ///
-    /// let mut h: HashMap<String, Vec<usize>> = HashMap::new();
+    /// let mut h: HashMap<String, usize> = HashMap::new();
     ///
-    /// for j in 0..3 {
-    ///     for i in 0..3 {
-    ///         h.add_element(&format!("NAME{}",i), i);
-    ///     }
+    /// for i in 0..10 {
+    ///     h.add_element(&format!("NAME{}",i), i);
     /// }
     ///
-    /// assert_eq!(h.keys().len(), 3);
-    /// assert_eq!(h.get("NAME0").unwrap(), &vec![0,0,0]);
-    /// assert_eq!(h.get("NAME1").unwrap(), &vec![1,1,1]);
-    /// assert_eq!(h.get("NAME2").unwrap(), &vec![2,2,2]);
+    /// h.insert_element(&"NEW".to_string(), 5);
+    /// assert_eq!(h.keys().len(), 11);
+    /// assert_eq!(h.get("NAME4").unwrap(), &4);
+    /// assert_eq!(h.get("NEW").unwrap(), &5);
+    /// assert_eq!(h.get("NAME5").unwrap(), &6);
+    /// assert_eq!(h.get("NAME9").unwrap(), &10);
+    /// ```
+    ///
     /// ```
-    fn add_element(&mut self, name: &str, index: usize) {
-        self.entry(name.to_string())
-            .or_insert(Vec::new())
-            .push(index);
-    }
+    /// use std::collections::{BTreeMap, HashMap};
+    /// use nec::adjustable::Adjustable;
+    ///
+    /// let mut h: HashMap<String, Vec<usize>> = HashMap::new();
+    ///
+    /// // 0 1 2 3 4
+    /// // A B A B A
+    /// h.insert("A".to_string(), vec![0,2,4]);
+    /// h.insert("B".to_string(), vec![1,3]);
+    ///
+    /// h.insert_element(&"B".to_string(), 2);
+    /// assert_eq!(h.get("A").unwrap(), &vec![0,3,5]);
+    /// assert_eq!(h.get("B").unwrap(), &vec![1,2,4]);
+    ///
+    /// h.insert_element(&"C".to_string(), 0);
+    /// assert_eq!(h.get("A").unwrap(), &vec![1,4,6]);
+    /// assert_eq!(h.get("B").unwrap(), &vec![2,3,5]);
+    /// assert_eq!(h.get("C").unwrap(), &vec![0]);
+    ///
+    /// h.insert_element(&"A".to_string(), 7);
+    /// assert_eq!(h.get("A").unwrap(), &vec![1,4,6,7]);
+    /// ```
+    fn insert_element(&mut self, name: &K, index: usize);
+
+    /// Returns the index of the element named `name` for maps of unique names, or `None` for maps of duplicated
+    /// names.
+    fn already_in(&mut self, name: &K) -> Option<usize>;
 
+    /// Removes only the `index` entry for `name`, without shifting any other index.
+    ///
     /// # Examples
     ///
     /// ```
-    /// use std::collections::HashMap;
+    /// use std::collections::{BTreeMap, HashMap};
+    /// use nec::adjustable::Adjustable;
+    ///
+    /// let mut h: HashMap<String, usize> = HashMap::new();
+    ///
+    /// for i in 0..10 {
+    ///     h.add_element(&format!("NAME{}",i), i);
+    /// }
+    ///
+    /// h.remove_index(&"NAME5".to_string(), 5);
+    /// assert_eq!(h.keys().len(), 9);
+    /// assert_eq!(h.get("NAME9").unwrap(), &9);
+    /// ```
+    ///
+    /// ```
+    /// use std::collections::{BTreeMap, HashMap};
     /// use nec::adjustable::Adjustable;
     ///
     /// let mut h: HashMap<String, Vec<usize>> = HashMap::new();
     ///
-    /// // set a specific data set for testing:
-    /// // 0 1 2 3 4 5 6 7 8 9
-    /// // A B A A B A A B B A
+    /// h.insert("A".to_string(), vec![0,2,4]);
+    /// h.insert("B".to_string(), vec![1,3]);
     ///
-    /// h.insert("A".to_string(), vec![0,2,3,5,6,9]);
-    /// h.insert("B".to_string(), vec![1,4,7,8]);
+    /// h.remove_index(&"A".to_string(), 2);
+    /// assert_eq!(h.get("A").unwrap(), &vec![0,4]);
+    /// assert_eq!(h.get("B").unwrap(), &vec![1,3]);
     ///
-    /// h.delete_element("A",0);
-    /// assert_eq!(h.get("A").unwrap(), &vec![1,2,4,5,8]);
-    /// assert_eq!(h.get("B").unwrap(), &vec![0,3,6,7]);
+    /// h.remove_index(&"B".to_string(), 1);
+    /// h.remove_index(&"B".to_string(), 3);
+    /// assert!(!h.contains_key("B"));
+    /// ```
+    fn remove_index(&mut self, name: &K, index: usize);
+
+    /// Moves the `from` entry for `name` to `to`, without shifting any other index.
     ///
-    /// h.delete_element("A",2);
-    /// assert_eq!(h.get("A").unwrap(), &vec![1,3,4,7]);
-    /// assert_eq!(h.get("B").unwrap(), &vec![0,2,5,6]);
+    /// # Examples
     ///
-    /// h.delete_element("A",7);
-    /// assert_eq!(h.get("A").unwrap(), &vec![1,3,4]);
-    /// assert_eq!(h.get("B").unwrap(), &vec![0,2,5,6]);
+    /// ```
+    /// use std::collections::{BTreeMap, HashMap};
+    /// use nec::adjustable::Adjustable;
     ///
-    /// h.delete_element("A",3);
-    /// assert_eq!(h.get("A").unwrap(), &vec![1,3]);
-    /// assert_eq!(h.get("B").unwrap(), &vec![0,2,4,5]);
+    /// let mut h: HashMap<String, usize> = HashMap::new();
     ///
-    /// h.delete_element("A",1);
-    /// assert_eq!(h.get("A").unwrap(), &vec![2]);
-    /// assert_eq!(h.get("B").unwrap(), &vec![0,1,3,4]);
+    /// for i in 0..10 {
+    ///     h.add_element(&format!("NAME{}",i), i);
+    /// }
     ///
-    /// h.delete_element("A",2);
-    /// assert!(!h.contains_key("A"));
-    /// assert_eq!(h.get("B").unwrap(), &vec![0,1,2,3]);
+    /// h.move_index(&"NAME9".to_string(), 9, 5);
+    /// assert_eq!(h.get("NAME9").unwrap(), &5);
+    /// assert_eq!(h.get("NAME5").unwrap(), &5);
+    /// ```
     ///
-    /// h.delete_element("B",0);
-    /// assert_eq!(h.get("B").unwrap(), &vec![0,1,2]);
+    /// ```
+    /// use std::collections::{BTreeMap, HashMap};
+    /// use nec::adjustable::Adjustable;
     ///
-    /// h.delete_element("B",2);
-    /// assert_eq!(h.get("B").unwrap(), &vec![0,1]);
+    /// let mut h: HashMap<String, Vec<usize>> = HashMap::new();
     ///
-    /// h.delete_element("B",0);
-    /// assert_eq!(h.get("B").unwrap(), &vec![0]);
+    /// h.insert("A".to_string(), vec![0,2,4]);
+    /// h.insert("B".to_string(), vec![1,3]);
     ///
-    /// h.delete_element("B",0);
-    /// assert!(!h.contains_key("B"));
+    /// h.move_index(&"A".to_string(), 4, 1);
+    /// assert_eq!(h.get("A").unwrap(), &vec![0,1,2]);
     /// ```
-    fn delete_element(&mut self, name: &str, index: usize) {
-        // remove the index from list of indexes. Don't use remove_item() fn for the moment
-        self.get_mut(name).unwrap().retain(|&i| i != index);
+    fn move_index(&mut self, name: &K, from: usize, to: usize);
+}
 
-        // if not more indexes, remove key
-        if self.get(name).unwrap().is_empty() {
-            self.remove(name);
-        }
+// implements Adjustable for a std map of names vs. index, the same way whatever the map type
+macro_rules! adjustable_unique {
+    ($(#[$attr:meta])* $map:ident<K $(, $s:ident)*> where $($bounds:tt)+) => {
+        $(#[$attr])*
+        impl<K, $($s),*> Adjustable<K> for $map<K, usize $(, $s)*>
+        where
+            $($bounds)+
+        {
+            fn add_element(&mut self, name: &K, index: usize) {
+                self.insert(name.clone(), index);
+            }
 
-        // for all indexes above, we need to remove 1
-        for v in self.values_mut() {
-            for j in v {
-                if *j > index {
-                    *j -= 1;
+            fn delete_element(&mut self, name: &K, index: usize) {
+                self.remove(name);
+
+                // for all indexes above, we need to remove 1
+                for j in self.values_mut() {
+                    if *j > index {
+                        *j -= 1;
+                    }
+                }
+            }
+
+            fn replace_element(&mut self, name: &K, index: usize) {
+                self.insert(name.clone(), index);
+            }
+
+            fn insert_element(&mut self, name: &K, index: usize) {
+                // for all indexes from the insertion point, we need to add 1
+                for j in self.values_mut() {
+                    if *j >= index {
+                        *j += 1;
+                    }
                 }
+
+                self.insert(name.clone(), index);
+            }
+
+            fn already_in(&mut self, name: &K) -> Option<usize> {
+                self.get(name).cloned()
+            }
+
+            fn remove_index(&mut self, name: &K, _index: usize) {
+                self.remove(name);
+            }
+
+            fn move_index(&mut self, name: &K, _from: usize, to: usize) {
+                self.insert(name.clone(), to);
             }
         }
-    }
+    };
+}
+
+// implements Adjustable for a std map of names vs. sorted indexes, the same way whatever the map type
+macro_rules! adjustable_duplicated {
+    ($(#[$attr:meta])* $map:ident<K $(, $s:ident)*> where $($bounds:tt)+) => {
+        $(#[$attr])*
+        impl<K, $($s),*> Adjustable<K> for $map<K, Vec<usize> $(, $s)*>
+        where
+            $($bounds)+
+        {
+            fn add_element(&mut self, name: &K, index: usize) {
+                let v = self.entry(name.clone()).or_default();
+                let pos = v.binary_search(&index).unwrap_or_else(|p| p);
+                v.insert(pos, index);
+            }
+
+            fn delete_element(&mut self, name: &K, index: usize) {
+                // remove the index from list of indexes. Don't use remove_item() fn for the moment
+                self.get_mut(name).unwrap().retain(|&i| i != index);
+
+                // if not more indexes, remove key
+                if self.get(name).unwrap().is_empty() {
+                    self.remove(name);
+                }
+
+                // for all indexes above, we need to remove 1
+                for v in self.values_mut() {
+                    for j in v {
+                        if *j > index {
+                            *j -= 1;
+                        }
+                    }
+                }
+            }
+
+            fn replace_element(&mut self, _name: &K, _index: usize) {}
 
-    fn replace_element(&mut self, name: &str, index: usize) {
-        //self.insert(name.to_string(), index);
-    }
+            fn insert_element(&mut self, name: &K, index: usize) {
+                // for all indexes from the insertion point, we need to add 1
+                for v in self.values_mut() {
+                    for j in v {
+                        if *j >= index {
+                            *j += 1;
+                        }
+                    }
+                }
+
+                // keep indexes sorted
+                let v = self.entry(name.clone()).or_default();
+                let pos = v.binary_search(&index).unwrap_or_else(|p| p);
+                v.insert(pos, index);
+            }
+
+            fn already_in(&mut self, _name: &K) -> Option<usize> {
+                None
+            }
+
+            fn remove_index(&mut self, name: &K, index: usize) {
+                let v = self.get_mut(name).unwrap();
+                if let Ok(pos) = v.binary_search(&index) {
+                    v.remove(pos);
+                }
+
+                // if not more indexes, remove key
+                if v.is_empty() {
+                    self.remove(name);
+                }
+            }
 
-    fn already_in(&mut self, _name: &str) -> Option<usize> {
-        None
-    }
+            fn move_index(&mut self, name: &K, from: usize, to: usize) {
+                // keep indexes sorted
+                let v = self.get_mut(name).unwrap();
+                if let Ok(pos) = v.binary_search(&from) {
+                    v.remove(pos);
+                }
+                let pos = v.binary_search(&to).unwrap_or_else(|p| p);
+                v.insert(pos, to);
+            }
+        }
+    };
 }
+
+adjustable_unique!(
+    /// Case of non-duplicated elements.
+    ///
+    /// Example:
+    ///
+    /// list -> [("Elem1", e1), ("Elem4", e4), ("Elem3", e3), ("Elem2", e2)]
+    /// hash -> {("Elem1", 0), ("Elem2", 3), ("Elem3", 2), ("Elem4", 1)}
+    ///
+    HashMap<K, S> where K: Hash + Eq + Clone, S: BuildHasher
+);
+
+adjustable_duplicated!(
+    /// Case of duplicated elements possible. This case is much more complicated, because indexes are
+    /// stored in a vector.
+    ///
+    /// Example:
+    ///
+    /// list -> ("Elem1", e1), ("Elem2, e2"), ("Elem3, e3"), ("Elem1", e1), ("Elem2", e2)
+    /// hash -> ("Elem1", [0,3]), ("Elem2", [1,4]), ("Elem3", [2])
+    ///
+    HashMap<K, S> where K: Hash + Eq + Clone, S: BuildHasher
+);
+
+adjustable_unique!(
+    /// Case of non-duplicated elements, with names kept sorted.
+    BTreeMap<K> where K: Ord + Clone
+);
+
+adjustable_duplicated!(
+    /// Case of duplicated elements, with names kept sorted.
+    BTreeMap<K> where K: Ord + Clone
+);
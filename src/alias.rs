@@ -0,0 +1,41 @@
+//! Formats of the aliases given to the elements of a DNEC collection. An alias is a unique name built from an
+//! element's name and a number, like `Hydrogen#1`, which refers to a single element even when several ones share
+//! the same name. An element keeps its alias until it's removed from the collection, whatever happens to the
+//! other elements, and even if it's renamed. Extra aliases could also be added to the elements of any collection
+//! with `add_alias()`.
+//!
+//! # Examples
+//! ```
+//! use nec::alias::hash_suffix;
+//! use nec::nec::DNEC;
+//!
+//! struct Atom { proton: u8, neutron: u8, };
+//! let mut water = DNEC::<Atom>::with_alias_format(hash_suffix);
+//!
+//! water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+//! water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+//! water.push("Oxygen", Atom{ proton:8, neutron:8 });
+//!
+//! water.remove(0);
+//! assert_eq!(water.get_by_alias("Hydrogen#1").unwrap().neutron, 1);
+//! assert!(water.get_by_alias("Hydrogen#0").is_none());
+//! assert_eq!(water[1].alias.as_ref().unwrap(), "Oxygen#0");
+//! ```
+use std::fmt::Display;
+
+/// Builds an alias from an element's name and a number. Different numbers must give different aliases.
+/// Closures not capturing their environment could also be used.
+pub type AliasFormat<K> = fn(&K, usize) -> K;
+
+/// Formats aliases as the name followed by `#` and the number.
+///
+/// # Examples
+///
+/// ```
+/// use nec::alias::hash_suffix;
+///
+/// assert_eq!(hash_suffix(&"Hydrogen".to_string(), 1), "Hydrogen#1");
+/// ```
+pub fn hash_suffix<K: Display + From<String>>(name: &K, n: usize) -> K {
+    K::from(format!("{}#{}", name, n))
+}
@@ -0,0 +1,425 @@
+//! Entry API for named elements collections, in the style of `HashMap::entry()`. An entry is
+//! either occupied, when at least one element is already named after the entry's name, or vacant.
+//!
+//! # Examples
+//! ```
+//! use nec::nec::UNEC;
+//!
+//! let mut counters = UNEC::<u32>::new();
+//!
+//! for word in "a b a c a b".split_whitespace() {
+//!     *counters.entry(word).or_insert(0) += 1;
+//! }
+//!
+//! assert_eq!(counters["a"].elem, 3);
+//! assert_eq!(counters["b"].elem, 2);
+//! assert_eq!(counters["c"].elem, 1);
+//! ```
+use std::collections::HashMap;
+
+use map::NameMap;
+use nec::{ElementBundle, Indexable, NamedElementsCollection, NecByName};
+use normalizer::{Normalizer, Verbatim};
+
+/// A view into a single name of a collection, which may either be vacant or occupied.
+pub enum Entry<
+    'a,
+    Element: 'a,
+    Indexes: 'a + Indexable,
+    K: 'a = String,
+    M: 'a = HashMap<K, Indexes>,
+    N: 'a = Verbatim,
+> {
+    /// At least one element has this name.
+    Occupied(OccupiedEntry<'a, Element, Indexes, K, M, N>),
+    /// No element has this name.
+    Vacant(VacantEntry<'a, Element, Indexes, K, M, N>),
+}
+
+/// A view into an occupied entry. For a DNEC, methods dealing with a single element refer to the first
+/// element having the entry's name.
+pub struct OccupiedEntry<
+    'a,
+    Element: 'a,
+    Indexes: 'a + Indexable,
+    K: 'a = String,
+    M: 'a = HashMap<K, Indexes>,
+    N: 'a = Verbatim,
+> {
+    collection: &'a mut NamedElementsCollection<Element, Indexes, K, M, N>,
+    name: K,
+    // index of the first element having this name
+    index: usize,
+}
+
+/// A view into a vacant entry.
+pub struct VacantEntry<
+    'a,
+    Element: 'a,
+    Indexes: 'a + Indexable,
+    K: 'a = String,
+    M: 'a = HashMap<K, Indexes>,
+    N: 'a = Verbatim,
+> {
+    collection: &'a mut NamedElementsCollection<Element, Indexes, K, M, N>,
+    name: K,
+}
+
+impl<'a, Element, Indexes, K, M, N> Entry<'a, Element, Indexes, K, M, N>
+where
+    M: NameMap<K, Indexes>,
+    K: Eq + Clone,
+    N: Normalizer<K>,
+    Indexes: Indexable,
+{
+    // only built by NamedElementsCollection::entry()
+    pub(crate) fn new(
+        collection: &'a mut NamedElementsCollection<Element, Indexes, K, M, N>,
+        name: K,
+    ) -> Self {
+        let index = collection.lookup_nth(&name, 0, false);
+
+        match index {
+            Some(index) => Entry::Occupied(OccupiedEntry {
+                collection,
+                name,
+                index,
+            }),
+            None => Entry::Vacant(VacantEntry { collection, name }),
+        }
+    }
+
+    /// Returns the entry's name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// let mut counters = UNEC::<u32>::new();
+    /// assert_eq!(counters.entry("a").key(), "a");
+    /// ```
+    pub fn key(&self) -> &K {
+        match *self {
+            Entry::Occupied(ref e) => e.key(),
+            Entry::Vacant(ref e) => e.key(),
+        }
+    }
+
+    /// Ensures an element is in the entry by adding `default` if empty, and returns a mutable reference
+    /// on the element.
+    ///
+    /// # Arguments
+    /// * `default` - Element added if the entry is vacant
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// let mut counters = UNEC::<u32>::new();
+    ///
+    /// *counters.entry("a").or_insert(10) += 1;
+    /// *counters.entry("a").or_insert(10) += 1;
+    /// assert_eq!(counters["a"].elem, 12);
+    /// ```
+    pub fn or_insert(self, default: Element) -> &'a mut Element {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default),
+        }
+    }
+
+    /// Ensures an element is in the entry by adding the result of `default` if empty, and returns a
+    /// mutable reference on the element.
+    ///
+    /// # Arguments
+    /// * `default` - Function called if the entry is vacant
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// let mut lists = UNEC::<Vec<u32>>::new();
+    ///
+    /// lists.entry("a").or_insert_with(Vec::new).push(1);
+    /// lists.entry("a").or_insert_with(Vec::new).push(2);
+    /// assert_eq!(lists["a"].elem, vec![1, 2]);
+    /// ```
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut Element
+    where
+        F: FnOnce() -> Element,
+    {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default()),
+        }
+    }
+
+    /// Ensures an element is in the entry by adding the default element if empty, and returns a
+    /// mutable reference on the element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// let mut counters = UNEC::<u32>::new();
+    ///
+    /// *counters.entry("a").or_default() += 1;
+    /// assert_eq!(counters["a"].elem, 1);
+    /// ```
+    pub fn or_default(self) -> &'a mut Element
+    where
+        Element: Default,
+    {
+        self.or_insert_with(Element::default)
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any potential insert.
+    ///
+    /// # Arguments
+    /// * `f` - Function called on the element if the entry is occupied
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// let mut counters = UNEC::<u32>::new();
+    ///
+    /// counters.entry("a").and_modify(|c| *c += 1).or_insert(1);
+    /// counters.entry("a").and_modify(|c| *c += 1).or_insert(1);
+    /// assert_eq!(counters["a"].elem, 2);
+    /// ```
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut Element),
+    {
+        match self {
+            Entry::Occupied(mut e) => {
+                f(e.get_mut());
+                Entry::Occupied(e)
+            }
+            Entry::Vacant(e) => Entry::Vacant(e),
+        }
+    }
+}
+
+impl<'a, Element, Indexes, K, M, N> OccupiedEntry<'a, Element, Indexes, K, M, N>
+where
+    M: NameMap<K, Indexes>,
+    K: Eq + Clone,
+    N: Normalizer<K>,
+    Indexes: Indexable,
+{
+    /// Returns the entry's name.
+    pub fn key(&self) -> &K {
+        &self.name
+    }
+
+    /// Returns the index of the element in the collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    /// use nec::entry::Entry;
+    ///
+    /// let mut counters = UNEC::<u32>::new();
+    /// counters.push("a", 1);
+    /// counters.push("b", 1);
+    ///
+    /// match counters.entry("b") {
+    ///     Entry::Occupied(e) => assert_eq!(e.index(), 1),
+    ///     Entry::Vacant(_) => unreachable!(),
+    /// }
+    /// ```
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns a reference on the element.
+    pub fn get(&self) -> &Element {
+        &self.collection.list[self.index].elem
+    }
+
+    /// Returns a mutable reference on the element.
+    pub fn get_mut(&mut self) -> &mut Element {
+        self.collection.elem_mut(self.index)
+    }
+
+    /// Converts the entry into a mutable reference on the element, bound to the collection's lifetime.
+    pub fn into_mut(self) -> &'a mut Element {
+        self.collection.elem_mut(self.index)
+    }
+
+    /// Replaces the element in place and returns the former one.
+    ///
+    /// # Arguments
+    /// * `element` - Element structure
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    /// use nec::entry::Entry;
+    ///
+    /// let mut counters = UNEC::<u32>::new();
+    /// counters.push("a", 1);
+    ///
+    /// if let Entry::Occupied(mut e) = counters.entry("a") {
+    ///     assert_eq!(e.insert(5), 1);
+    /// }
+    /// assert_eq!(counters["a"].elem, 5);
+    /// ```
+    pub fn insert(&mut self, element: Element) -> Element {
+        ::std::mem::replace(self.get_mut(), element)
+    }
+
+    /// Removes the element from the collection and returns it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    /// use nec::entry::Entry;
+    ///
+    /// let mut counters = UNEC::<u32>::new();
+    /// counters.push("a", 1);
+    ///
+    /// if let Entry::Occupied(e) = counters.entry("a") {
+    ///     assert_eq!(e.remove().elem, 1);
+    /// }
+    /// assert!(counters.is_empty());
+    /// ```
+    pub fn remove(self) -> ElementBundle<Element, K> {
+        self.collection.remove(self.index)
+    }
+}
+
+impl<'a, Element, K, M, N> OccupiedEntry<'a, Element, Vec<usize>, K, M, N>
+where
+    M: NameMap<K, Vec<usize>>,
+    K: Eq + Clone,
+    N: Normalizer<K>,
+{
+    /// Returns an iterator over all elements having the entry's name, in the collection order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    /// use nec::entry::Entry;
+    ///
+    /// let mut water = DNEC::<u8>::new();
+    /// water.push("Hydrogen", 1);
+    /// water.push("Oxygen", 8);
+    /// water.push("Hydrogen", 1);
+    ///
+    /// if let Entry::Occupied(e) = water.entry("Hydrogen") {
+    ///     assert_eq!(e.iter().count(), 2);
+    /// }
+    /// ```
+    pub fn iter(&self) -> NecByName<'_, Element, K> {
+        self.collection.iter_by_name(&self.name)
+    }
+
+    /// Returns mutable references on all elements having the entry's name, in the collection order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    /// use nec::entry::Entry;
+    ///
+    /// let mut water = DNEC::<u8>::new();
+    /// water.push("Hydrogen", 0);
+    /// water.push("Oxygen", 8);
+    /// water.push("Hydrogen", 0);
+    ///
+    /// if let Entry::Occupied(mut e) = water.entry("Hydrogen") {
+    ///     for h in e.get_all_mut() {
+    ///         *h = 1;
+    ///     }
+    /// }
+    /// assert_eq!(water.get_by_name("Hydrogen").unwrap(), vec![&1, &1]);
+    /// ```
+    pub fn get_all_mut(&mut self) -> Vec<&mut Element> {
+        self.collection.get_by_name_mut(&self.name).unwrap()
+    }
+
+    /// Adds another element with the entry's name at the end of the collection.
+    ///
+    /// # Arguments
+    /// * `element` - Element structure
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    /// use nec::entry::Entry;
+    ///
+    /// let mut water = DNEC::<u8>::new();
+    /// water.push("Hydrogen", 1);
+    /// water.push("Oxygen", 8);
+    ///
+    /// if let Entry::Occupied(mut e) = water.entry("Hydrogen") {
+    ///     e.push(1);
+    /// }
+    /// assert_eq!(water.get_by_name("Hydrogen").unwrap().len(), 2);
+    /// assert_eq!(water.get_name(2).unwrap(), "Hydrogen");
+    /// ```
+    pub fn push(&mut self, element: Element) {
+        self.collection.push(self.name.clone(), element);
+    }
+}
+
+impl<'a, Element, Indexes, K, M, N> VacantEntry<'a, Element, Indexes, K, M, N>
+where
+    M: NameMap<K, Indexes>,
+    K: Eq + Clone,
+    N: Normalizer<K>,
+    Indexes: Indexable,
+{
+    /// Returns the entry's name.
+    pub fn key(&self) -> &K {
+        &self.name
+    }
+
+    /// Takes ownership of the entry's name.
+    pub fn into_key(self) -> K {
+        self.name
+    }
+
+    /// Adds the element at the end of the collection with the entry's name, and returns a mutable reference
+    /// on it.
+    ///
+    /// # Arguments
+    /// * `element` - Element structure
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    /// use nec::entry::Entry;
+    ///
+    /// let mut counters = UNEC::<u32>::new();
+    ///
+    /// if let Entry::Vacant(e) = counters.entry("a") {
+    ///     *e.insert(1) += 1;
+    /// }
+    /// assert_eq!(counters["a"].elem, 2);
+    /// ```
+    pub fn insert(self, element: Element) -> &'a mut Element {
+        let index = self.collection.push_new_bundle(ElementBundle {
+            elem: element,
+            name: self.name,
+            alias: None,
+            aliases: Vec::new(),
+        });
+
+        self.collection.elem_mut(index)
+    }
+}
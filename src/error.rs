@@ -0,0 +1,104 @@
+//! Errors returned by the collection methods which could fail without being a programming error.
+use std::error::Error;
+use std::fmt;
+
+/// Errors which could occur when manipulating a collection by names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NecError<K = String> {
+    /// The name is already used by another element in a collection where names are unique.
+    DuplicateName(K),
+    /// The name is not found in the collection.
+    NameNotFound(K),
+    /// No secondary index has this name.
+    IndexNotFound(String),
+    /// The secondary index with this name has keys of another type than the one looked up.
+    WrongKeyType(String),
+}
+
+impl<K: fmt::Display> fmt::Display for NecError<K> {
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::error::NecError;
+    ///
+    /// let e = NecError::DuplicateName("Hydrogen".to_string());
+    /// assert_eq!(e.to_string(), "name 'Hydrogen' is already in the collection");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NecError::DuplicateName(ref name) => {
+                write!(f, "name '{}' is already in the collection", name)
+            }
+            NecError::NameNotFound(ref name) => {
+                write!(f, "name '{}' is not found in the collection", name)
+            }
+            NecError::IndexNotFound(ref name) => {
+                write!(f, "index '{}' is not found in the collection", name)
+            }
+            NecError::WrongKeyType(ref name) => {
+                write!(f, "index '{}' has keys of another type", name)
+            }
+        }
+    }
+}
+
+impl<K: fmt::Debug + fmt::Display> Error for NecError<K> {}
+
+/// Error found when reading a collection from the `name = value` text format, at a 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line of the error.
+    pub line: usize,
+    /// Column of the error, in characters.
+    pub column: usize,
+    /// What went wrong.
+    pub kind: ParseErrorKind,
+}
+
+/// Kinds of errors found when reading the `name = value` text format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The line has a name but no `=` after it.
+    MissingEquals,
+    /// The name before `=` is empty.
+    EmptyName,
+    /// A quoted name or value is not closed before the end of the line.
+    UnterminatedQuote,
+    /// An unknown escape sequence is found in a quoted name or value.
+    InvalidEscape(String),
+    /// Characters are found after a quoted name or value.
+    TrailingCharacters,
+    /// The value can't be converted to an element, with the conversion error message.
+    InvalidValue(String),
+    /// The name is already used by another element in a collection where names are unique.
+    DuplicateName(String),
+}
+
+impl fmt::Display for ParseError {
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::error::{ParseError, ParseErrorKind};
+    ///
+    /// let e = ParseError { line: 3, column: 7, kind: ParseErrorKind::MissingEquals };
+    /// assert_eq!(e.to_string(), "line 3, column 7: expected '=' after the name");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match self.kind {
+            ParseErrorKind::MissingEquals => write!(f, "expected '=' after the name"),
+            ParseErrorKind::EmptyName => write!(f, "name is empty"),
+            ParseErrorKind::UnterminatedQuote => write!(f, "missing closing quote"),
+            ParseErrorKind::InvalidEscape(ref s) => write!(f, "invalid escape sequence '{}'", s),
+            ParseErrorKind::TrailingCharacters => {
+                write!(f, "unexpected characters after the closing quote")
+            }
+            ParseErrorKind::InvalidValue(ref s) => write!(f, "invalid value: {}", s),
+            ParseErrorKind::DuplicateName(ref name) => {
+                write!(f, "name '{}' is already in the collection", name)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
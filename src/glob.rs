@@ -0,0 +1,97 @@
+// Wildcard matching of names, used by the *_glob() methods of the collections. Supported wildcards are:
+//
+// * `*` matches any sequence of characters, including an empty one
+// * `?` matches exactly one character
+// * `[abc]` matches one of the characters between brackets, `[a-z]` one character of the range, and
+//   `[!abc]` one character not between brackets
+//
+// A `[` without its closing `]` matches itself.
+
+// returns true if the whole `name` matches `pattern`
+pub(crate) fn matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    // positions in pattern and name
+    let (mut p, mut n) = (0, 0);
+
+    // position of the last star in pattern, and of the name char it matches up to
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+                continue;
+            }
+            Some('?') => {
+                p += 1;
+                n += 1;
+                continue;
+            }
+            Some('[') => {
+                if let Some((matched, len)) = match_class(&pattern[p..], name[n]) {
+                    if matched {
+                        p += len;
+                        n += 1;
+                        continue;
+                    }
+                } else if name[n] == '[' {
+                    p += 1;
+                    n += 1;
+                    continue;
+                }
+            }
+            Some(&c) if c == name[n] => {
+                p += 1;
+                n += 1;
+                continue;
+            }
+            _ => (),
+        }
+
+        // mismatch: let the last star match one more char, if any
+        match star {
+            Some((star_p, star_n)) => {
+                star = Some((star_p, star_n + 1));
+                p = star_p + 1;
+                n = star_n + 1;
+            }
+            None => return false,
+        }
+    }
+
+    // remaining pattern can only be stars
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+// matches `c` against the class starting `pattern`, and returns whether it matched and the class length,
+// or None if the class is not closed
+fn match_class(pattern: &[char], c: char) -> Option<(bool, usize)> {
+    let mut i = 1;
+    let negated = pattern.get(i) == Some(&'!');
+    if negated {
+        i += 1;
+    }
+
+    let mut matched = false;
+    let mut first = true;
+
+    // a `]` right after the opening bracket is part of the class
+    while first || pattern.get(i) != Some(&']') {
+        let low = *pattern.get(i)?;
+        first = false;
+
+        if pattern.get(i + 1) == Some(&'-') && pattern.get(i + 2).is_some_and(|&h| h != ']') {
+            let high = pattern[i + 2];
+            matched |= low <= c && c <= high;
+            i += 3;
+        } else {
+            matched |= low == c;
+            i += 1;
+        }
+    }
+
+    Some((matched != negated, i + 1))
+}
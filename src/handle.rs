@@ -0,0 +1,251 @@
+//! Handles of the elements of a collection. A handle refers to a single element from the time it's added until
+//! it's removed, whatever happens to the other elements: unlike indexes, handles stay valid when elements are
+//! removed, inserted or reordered. Once its element is removed or replaced, a handle is stale and is never
+//! given to another element.
+//!
+//! # Examples
+//! ```
+//! use nec::nec::DNEC;
+//!
+//! struct Atom { proton: u8, neutron: u8, };
+//! let mut water = DNEC::<Atom>::new();
+//!
+//! let h1 = water.push_handle("Hydrogen", Atom{ proton:1, neutron:0 });
+//! let o = water.push_handle("Oxygen", Atom{ proton:8, neutron:8 });
+//! water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+//!
+//! water.remove(0);
+//! water.sort_by_name();
+//! assert_eq!(water.get_by_handle(o).unwrap().proton, 8);
+//! assert!(water.get_by_handle(h1).is_none());
+//! ```
+
+use std::sync::OnceLock;
+
+use order::OrderTree;
+
+/// Reference to an element of a collection, made of a slot and of the generation of the slot when the handle
+/// was given. Handles are only meaningful for the collection which gave them, or its clones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    slot: usize,
+    generation: u32,
+}
+
+impl Handle {
+    /// Returns the handle's slot.
+    pub fn slot(&self) -> usize {
+        self.slot
+    }
+
+    /// Returns the handle's generation. It's bumped each time the slot's element is removed.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+// slot of a tombstone, an element removed from the collection but still in its list until compaction
+pub(crate) const TOMBSTONE: usize = usize::MAX;
+
+// order tree following the list, giving the positions of the slots in logarithmic time
+#[derive(Clone)]
+struct Positions {
+    tree: OrderTree,
+    // node of each slot in use
+    nodes: Vec<Option<usize>>,
+}
+
+impl Positions {
+    fn set(&mut self, slot: usize, node: Option<usize>) {
+        if slot == TOMBSTONE {
+            return;
+        }
+        if self.nodes.len() <= slot {
+            self.nodes.resize(slot + 1, None);
+        }
+        self.nodes[slot] = node;
+    }
+}
+
+// slots of the elements of a collection, following its list. A slot is used by at most one element at a time,
+// and its generation changes when it's released.
+//
+// Slots are found from indexes directly, but finding the index of a slot needs the positions tree. It's only
+// built when a handle is first looked up, then kept up to date, so collections whose handles are never used
+// don't pay for it
+#[derive(Clone, Default)]
+pub(crate) struct Handles {
+    generations: Vec<u32>,
+    // released slots, to be reused
+    free: Vec<usize>,
+    // slot of each element of the list, or TOMBSTONE
+    of_index: Vec<usize>,
+    // number of tombstones in the list
+    dead: usize,
+    positions: OnceLock<Positions>,
+}
+
+impl Handles {
+    pub(crate) fn get(&self, index: usize) -> Option<Handle> {
+        let slot = *self.of_index.get(index)?;
+        if slot == TOMBSTONE {
+            return None;
+        }
+        Some(Handle {
+            slot,
+            generation: self.generations[slot],
+        })
+    }
+
+    // returns the index of the element of the handle, or None if it's stale
+    pub(crate) fn index_of(&self, handle: Handle) -> Option<usize> {
+        if self.generations.get(handle.slot) != Some(&handle.generation) {
+            return None;
+        }
+        let positions = self.positions.get_or_init(|| self.build());
+        let node = (*positions.nodes.get(handle.slot)?)?;
+        Some(positions.tree.rank(node))
+    }
+
+    fn build(&self) -> Positions {
+        let mut positions = Positions {
+            tree: OrderTree::default(),
+            nodes: vec![None; self.generations.len()],
+        };
+        for &slot in &self.of_index {
+            let node = positions.tree.push(0);
+            positions.set(slot, Some(node));
+        }
+        positions
+    }
+
+    fn alloc(&mut self) -> usize {
+        match self.free.pop() {
+            Some(slot) => slot,
+            None => {
+                self.generations.push(0);
+                self.generations.len() - 1
+            }
+        }
+    }
+
+    fn release(&mut self, slot: usize) {
+        if slot == TOMBSTONE {
+            self.dead -= 1;
+            return;
+        }
+        self.generations[slot] = self.generations[slot].wrapping_add(1);
+        self.free.push(slot);
+        if let Some(positions) = self.positions.get_mut() {
+            positions.set(slot, None);
+        }
+    }
+
+    // gives a new slot to an element inserted at index
+    pub(crate) fn insert(&mut self, index: usize) {
+        let slot = self.alloc();
+        self.of_index.insert(index, slot);
+        if let Some(positions) = self.positions.get_mut() {
+            let node = positions.tree.insert(index, 0);
+            positions.set(slot, Some(node));
+        }
+    }
+
+    // releases the slot of an element removed at index
+    pub(crate) fn remove(&mut self, index: usize) {
+        let slot = self.of_index.remove(index);
+        if let Some(positions) = self.positions.get_mut() {
+            let node = positions.tree.select(index).unwrap();
+            positions.tree.remove(node);
+        }
+        self.release(slot);
+    }
+
+    pub(crate) fn swap_remove(&mut self, index: usize) {
+        let last = self.of_index.len() - 1;
+        self.swap(index, last);
+        self.remove(last);
+    }
+
+    pub(crate) fn swap(&mut self, a: usize, b: usize) {
+        self.of_index.swap(a, b);
+        if let Some(positions) = self.positions.get_mut() {
+            let (na, nb) = (positions.tree.select(a), positions.tree.select(b));
+            positions.set(self.of_index[a], na);
+            positions.set(self.of_index[b], nb);
+        }
+    }
+
+    // turns the element at index into a tombstone, releasing its slot
+    pub(crate) fn kill(&mut self, index: usize) {
+        let slot = ::std::mem::replace(&mut self.of_index[index], TOMBSTONE);
+        self.release(slot);
+        self.dead += 1;
+    }
+
+    pub(crate) fn is_dead(&self, index: usize) -> bool {
+        self.of_index.get(index) == Some(&TOMBSTONE)
+    }
+
+    // returns the number of tombstones
+    pub(crate) fn dead(&self) -> usize {
+        self.dead
+    }
+
+    // gives a new slot to the element at index, replacing the previous one
+    pub(crate) fn renew(&mut self, index: usize) {
+        let old = self.of_index[index];
+        self.release(old);
+        let slot = self.alloc();
+        self.of_index[index] = slot;
+        if let Some(positions) = self.positions.get_mut() {
+            let node = positions.tree.select(index);
+            positions.set(slot, node);
+        }
+    }
+
+    // releases the slots of elements from len, or gives slots to new elements up to len
+    pub(crate) fn resize(&mut self, len: usize) {
+        while self.of_index.len() > len {
+            let last = self.of_index.len() - 1;
+            self.remove(last);
+        }
+        while self.of_index.len() < len {
+            let index = self.of_index.len();
+            self.insert(index);
+        }
+    }
+
+    // pairs the elements of the list with their slots, for changes of the whole list
+    pub(crate) fn attach<T>(&self, list: Vec<T>) -> Vec<(T, usize)> {
+        list.into_iter()
+            .zip(self.of_index.iter().cloned())
+            .collect()
+    }
+
+    // takes back the slots of elements paired by attach(), and releases the ones of missing elements.
+    // Tombstones stay where they were moved, missing ones are dropped. The positions tree is rebuilt when
+    // needed again
+    pub(crate) fn detach<T>(&mut self, pairs: Vec<(T, usize)>) -> Vec<T> {
+        let (list, slots): (Vec<T>, Vec<usize>) = pairs.into_iter().unzip();
+        let old = ::std::mem::replace(&mut self.of_index, slots);
+        self.positions = OnceLock::new();
+
+        let mut kept = vec![false; self.generations.len()];
+        self.dead = 0;
+        for &slot in &self.of_index {
+            if slot == TOMBSTONE {
+                self.dead += 1;
+            } else {
+                kept[slot] = true;
+            }
+        }
+        for slot in old {
+            if slot != TOMBSTONE && !kept[slot] {
+                self.release(slot);
+            }
+        }
+
+        list
+    }
+}
@@ -1,7 +1,28 @@
 pub mod adjustable;
+pub mod alias;
+pub mod entry;
+pub mod error;
+mod glob;
+pub mod handle;
+pub mod map;
+pub mod nameable;
 pub mod nec;
+pub mod normalizer;
+pub mod offset;
+mod order;
+pub mod policy;
+mod secondary;
+#[cfg(feature = "serde")]
+pub mod serialize;
+pub mod text;
+
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
 
 // setup module to build test data
+#[cfg(test)]
 mod setup {
 
     // setup data structures for all tests
@@ -10,17 +31,29 @@ mod setup {
         pub proton: usize,
         pub neutron: usize,
     }
-
 }
 
 #[cfg(test)]
 mod tests {
 
-    use nec::{DNEC, UNEC};
+    use alias::hash_suffix;
+    use entry::Entry;
+    use error::NecError;
+    use nameable::Nameable;
+    use nec::{NamedElementsCollection, SortedDNEC, SortedUNEC, DNEC, UNEC};
+    use normalizer::{AsciiCaseFold, FoldSeparators, Trim};
+    use policy::DuplicatePolicy;
     use setup::Atom;
+    use std::collections::hash_map::{DefaultHasher, RandomState};
+    use std::collections::HashMap;
+    use std::hash::BuildHasherDefault;
+    use std::panic;
 
     //use adjustable::Adjustable;
-    //use nameable::Nameable;
+
+    // DNEC storing the indexes of each name, to check the computed ones against
+    type VecDNEC<Element> =
+        NamedElementsCollection<Element, Vec<usize>, String, HashMap<String, Vec<usize>>>;
 
     // generic setup function for data, either with or without name
     #[test]
@@ -250,4 +283,2203 @@ mod tests {
         nec.clear();
         assert_eq!(nec.len(), 0);
     }
+
+    #[test]
+    fn test_remove() {
+        //---------------------------------------------------------------------------
+        // UNEC: remove first, middle and last elements
+        //---------------------------------------------------------------------------
+        let mut unec = UNEC::<Atom>::new();
+        for i in 0..10 {
+            unec.push(
+                format!("NAME{}", i),
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        let e = unec.remove(0);
+        assert_eq!(e.name, "NAME0");
+        assert_eq!(e.elem.proton, 0);
+        assert!(!unec.contains_name("NAME0"));
+
+        let e = unec.remove(4);
+        assert_eq!(e.name, "NAME5");
+        assert!(!unec.contains_name("NAME5"));
+
+        let e = unec.remove(unec.len() - 1);
+        assert_eq!(e.name, "NAME9");
+        assert!(!unec.contains_name("NAME9"));
+
+        // remaining names must still point to the right elements
+        assert_eq!(unec.len(), 7);
+        for &i in &[1, 2, 3, 4, 6, 7, 8] {
+            let name = format!("NAME{}", i);
+            assert_eq!(unec[name.as_str()].elem.proton, i);
+            assert_eq!(unec[name.as_str()].name, name);
+        }
+        for (i, e) in unec.iter().enumerate() {
+            assert_eq!(unec.hmap[&e.name], i);
+        }
+
+        // empty the collection from the front
+        while !unec.is_empty() {
+            let name = unec.get_name(0).unwrap().clone();
+            assert_eq!(unec.remove(0).name, name);
+            assert!(!unec.contains_name(&name));
+        }
+        assert!(unec.hmap.is_empty());
+
+        //---------------------------------------------------------------------------
+        // DNEC: interleaved names
+        //---------------------------------------------------------------------------
+        let mut dnec = DNEC::<Atom>::new();
+        for i in 0..12 {
+            let name = ["A", "B", "C"][i % 3];
+            dnec.push(
+                name,
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        let e = dnec.remove(11);
+        assert_eq!(e.name, "C");
+        assert_eq!(e.elem.proton, 11);
+
+        let e = dnec.remove(4);
+        assert_eq!(e.name, "B");
+        assert_eq!(e.elem.proton, 4);
+
+        let e = dnec.remove(0);
+        assert_eq!(e.name, "A");
+        assert_eq!(e.elem.proton, 0);
+
+        let protons = |nec: &DNEC<Atom>, name| -> Vec<usize> {
+            nec.get_by_name(name)
+                .unwrap()
+                .iter()
+                .map(|a| a.proton)
+                .collect()
+        };
+        assert_eq!(protons(&dnec, "A"), vec![3, 6, 9]);
+        assert_eq!(protons(&dnec, "B"), vec![1, 7, 10]);
+        assert_eq!(protons(&dnec, "C"), vec![2, 5, 8]);
+
+        // every index stored in the hashmap must point to an element of the same name
+        for (name, indexes) in &dnec.hmap {
+            for &i in &indexes {
+                assert_eq!(&dnec[i].name, name);
+            }
+        }
+
+        // remove all "C" elements one by one
+        while let Some(i) = dnec.iter().position(|e| e.name == "C") {
+            dnec.remove(i);
+        }
+        assert!(!dnec.contains_name("C"));
+        assert_eq!(protons(&dnec, "A"), vec![3, 6, 9]);
+        assert_eq!(protons(&dnec, "B"), vec![1, 7, 10]);
+    }
+
+    #[test]
+    fn test_insert() {
+        //---------------------------------------------------------------------------
+        // UNEC
+        //---------------------------------------------------------------------------
+        let mut unec = UNEC::<Atom>::new();
+        for i in 0..5 {
+            unec.push(
+                format!("NAME{}", i),
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        // insert at front, middle and end
+        assert!(unec
+            .insert(
+                0,
+                "FIRST",
+                Atom {
+                    proton: 100,
+                    neutron: 0
+                }
+            )
+            .is_none());
+        assert!(unec
+            .insert(
+                3,
+                "MIDDLE",
+                Atom {
+                    proton: 101,
+                    neutron: 0
+                }
+            )
+            .is_none());
+        let len = unec.len();
+        assert!(unec
+            .insert(
+                len,
+                "LAST",
+                Atom {
+                    proton: 102,
+                    neutron: 0
+                }
+            )
+            .is_none());
+
+        let names: Vec<_> = unec.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["FIRST", "NAME0", "NAME1", "MIDDLE", "NAME2", "NAME3", "NAME4", "LAST"]
+        );
+        for (i, e) in unec.iter().enumerate() {
+            assert_eq!(unec.hmap[&e.name], i);
+        }
+        assert_eq!(unec["MIDDLE"].elem.proton, 101);
+        assert_eq!(unec["NAME4"].elem.proton, 4);
+
+        // existing name: replaced in place
+        let old = unec
+            .insert(
+                0,
+                "NAME2",
+                Atom {
+                    proton: 42,
+                    neutron: 0,
+                },
+            )
+            .unwrap();
+        assert_eq!(old.elem.proton, 2);
+        assert_eq!(unec.len(), 8);
+        assert_eq!(unec.get_name(4).unwrap(), "NAME2");
+        assert_eq!(unec["NAME2"].elem.proton, 42);
+
+        // mix with removals
+        unec.remove(0);
+        unec.insert(
+            1,
+            "AGAIN",
+            Atom {
+                proton: 103,
+                neutron: 0,
+            },
+        );
+        for (i, e) in unec.iter().enumerate() {
+            assert_eq!(unec.hmap[&e.name], i);
+        }
+
+        //---------------------------------------------------------------------------
+        // DNEC
+        //---------------------------------------------------------------------------
+        let mut dnec = DNEC::<Atom>::new();
+        for i in 0..6 {
+            dnec.push(
+                ["A", "B"][i % 2],
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        assert!(dnec
+            .insert(
+                0,
+                "B",
+                Atom {
+                    proton: 10,
+                    neutron: 0
+                }
+            )
+            .is_none());
+        assert!(dnec
+            .insert(
+                4,
+                "A",
+                Atom {
+                    proton: 11,
+                    neutron: 0
+                }
+            )
+            .is_none());
+        assert!(dnec
+            .insert(
+                8,
+                "C",
+                Atom {
+                    proton: 12,
+                    neutron: 0
+                }
+            )
+            .is_none());
+        assert_eq!(dnec.len(), 9);
+
+        let protons = |nec: &DNEC<Atom>, name| -> Vec<usize> {
+            nec.get_by_name(name)
+                .unwrap()
+                .iter()
+                .map(|a| a.proton)
+                .collect()
+        };
+        assert_eq!(protons(&dnec, "A"), vec![0, 2, 11, 4]);
+        assert_eq!(protons(&dnec, "B"), vec![10, 1, 3, 5]);
+        assert_eq!(protons(&dnec, "C"), vec![12]);
+
+        for (name, indexes) in &dnec.hmap {
+            for &i in &indexes {
+                assert_eq!(&dnec[i].name, name);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_insert_out_of_bounds() {
+        let mut unec = UNEC::<Atom>::new();
+        unec.insert(
+            1,
+            "NAME",
+            Atom {
+                proton: 0,
+                neutron: 0,
+            },
+        );
+    }
+
+    #[test]
+    fn test_remove_by_name() {
+        //---------------------------------------------------------------------------
+        // UNEC
+        //---------------------------------------------------------------------------
+        let mut unec = UNEC::<Atom>::new();
+        for i in 0..10 {
+            unec.push(
+                format!("NAME{}", i),
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        assert_eq!(unec.remove_by_name("NAME0").unwrap().elem.proton, 0);
+        assert_eq!(unec.remove_by_name("NAME9").unwrap().elem.proton, 9);
+        assert_eq!(unec.remove_by_name("NAME4").unwrap().elem.proton, 4);
+        assert!(unec.remove_by_name("NAME4").is_none());
+        assert!(unec.remove_by_name("NAME100").is_none());
+
+        assert_eq!(unec.len(), 7);
+        for &i in &[1, 2, 3, 5, 6, 7, 8] {
+            assert_eq!(unec[format!("NAME{}", i).as_str()].elem.proton, i);
+        }
+
+        //---------------------------------------------------------------------------
+        // DNEC
+        //---------------------------------------------------------------------------
+        let mut dnec = DNEC::<Atom>::new();
+        for i in 0..12 {
+            dnec.push(
+                ["A", "B", "C"][i % 3],
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        let protons = |nec: &DNEC<Atom>, name| -> Vec<usize> {
+            nec.get_by_name(name)
+                .unwrap()
+                .iter()
+                .map(|a| a.proton)
+                .collect()
+        };
+
+        // nth
+        assert_eq!(dnec.remove_nth_by_name("B", 2).unwrap().elem.proton, 7);
+        assert!(dnec.remove_nth_by_name("B", 3).is_none());
+        assert!(dnec.remove_nth_by_name("D", 0).is_none());
+        assert_eq!(protons(&dnec, "B"), vec![1, 4, 10]);
+        assert_eq!(protons(&dnec, "C"), vec![2, 5, 8, 11]);
+
+        // all
+        let removed: Vec<_> = dnec
+            .remove_all_by_name("A")
+            .iter()
+            .map(|e| e.elem.proton)
+            .collect();
+        assert_eq!(removed, vec![0, 3, 6, 9]);
+        assert!(!dnec.contains_name("A"));
+        assert!(dnec.remove_all_by_name("A").is_empty());
+        assert_eq!(dnec.len(), 7);
+        assert_eq!(protons(&dnec, "B"), vec![1, 4, 10]);
+        assert_eq!(protons(&dnec, "C"), vec![2, 5, 8, 11]);
+
+        for (name, indexes) in &dnec.hmap {
+            for &i in &indexes {
+                assert_eq!(&dnec[i].name, name);
+            }
+        }
+
+        let removed: Vec<_> = dnec
+            .remove_all_by_name("C")
+            .iter()
+            .map(|e| e.elem.proton)
+            .collect();
+        assert_eq!(removed, vec![2, 5, 8, 11]);
+        assert_eq!(dnec.hmap.get("B").unwrap(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_swap_remove_pop() {
+        //---------------------------------------------------------------------------
+        // UNEC
+        //---------------------------------------------------------------------------
+        let mut unec = UNEC::<Atom>::new();
+        for i in 0..10 {
+            unec.push(
+                format!("NAME{}", i),
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        assert_eq!(unec.swap_remove(0).name, "NAME0");
+        assert_eq!(unec.get_name(0).unwrap(), "NAME9");
+        assert_eq!(unec.swap_remove(8).name, "NAME8");
+        assert_eq!(unec.pop().unwrap().name, "NAME7");
+        assert_eq!(unec.len(), 7);
+        assert!(!unec.contains_name("NAME0"));
+        assert!(!unec.contains_name("NAME7"));
+        assert!(!unec.contains_name("NAME8"));
+        for (i, e) in unec.iter().enumerate() {
+            assert_eq!(unec.hmap[&e.name], i);
+            assert_eq!(unec[e.name.as_str()].elem.proton, e.elem.proton);
+        }
+
+        while unec.pop().is_some() {}
+        assert!(unec.hmap.is_empty());
+        assert!(unec.pop().is_none());
+
+        //---------------------------------------------------------------------------
+        // DNEC
+        //---------------------------------------------------------------------------
+        let mut dnec = DNEC::<Atom>::new();
+        for i in 0..12 {
+            dnec.push(
+                ["A", "B", "C"][i % 3],
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        // C11 goes to index 1, before other C's
+        assert_eq!(dnec.swap_remove(1).elem.proton, 1);
+        // B10 goes to index 7
+        assert_eq!(dnec.swap_remove(7).elem.proton, 7);
+        assert_eq!(dnec.pop().unwrap().elem.proton, 9);
+
+        let protons = |nec: &DNEC<Atom>, name| -> Vec<usize> {
+            nec.get_by_name(name)
+                .unwrap()
+                .iter()
+                .map(|a| a.proton)
+                .collect()
+        };
+        assert_eq!(protons(&dnec, "A"), vec![0, 3, 6]);
+        assert_eq!(protons(&dnec, "B"), vec![4, 10]);
+        assert_eq!(protons(&dnec, "C"), vec![11, 2, 5, 8]);
+
+        for (name, indexes) in &dnec.hmap {
+            assert!(indexes.windows(2).all(|w| w[0] < w[1]));
+            for &i in &indexes {
+                assert_eq!(&dnec[i].name, name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_nameable() {
+        #[derive(Debug)]
+        struct Isotope {
+            symbol: String,
+            neutron: usize,
+        }
+        impl Nameable for Isotope {
+            fn name(&self) -> &str {
+                &self.symbol
+            }
+        }
+
+        let isotopes = || {
+            (0..6).map(|i| Isotope {
+                symbol: ["H", "He", "Li"][i % 3].to_string(),
+                neutron: i,
+            })
+        };
+
+        //---------------------------------------------------------------------------
+        // push_named
+        //---------------------------------------------------------------------------
+        let mut unec = UNEC::<Isotope>::new();
+        for isotope in isotopes() {
+            unec.push_named(isotope);
+        }
+        assert_eq!(unec.len(), 3);
+        assert_eq!(unec["H"].elem.neutron, 3);
+        assert_eq!(unec["Li"].elem.neutron, 5);
+
+        let mut dnec = DNEC::<Isotope>::new();
+        for isotope in isotopes() {
+            dnec.push_named(isotope);
+        }
+        assert_eq!(dnec.len(), 6);
+        assert_eq!(dnec.get_by_name("He").unwrap().len(), 2);
+
+        //---------------------------------------------------------------------------
+        // From & FromIterator
+        //---------------------------------------------------------------------------
+        let unec = UNEC::<Isotope>::from(isotopes().collect::<Vec<_>>());
+        assert_eq!(unec.len(), 3);
+        assert_eq!(unec.get_name(1).unwrap(), "He");
+
+        let dnec: DNEC<Isotope> = isotopes().collect();
+        assert_eq!(dnec.len(), 6);
+        for (i, e) in dnec.iter().enumerate() {
+            assert_eq!(e.name, e.elem.symbol);
+            assert_eq!(e.elem.neutron, i);
+        }
+
+        //---------------------------------------------------------------------------
+        // tuples
+        //---------------------------------------------------------------------------
+        let dnec: DNEC<(&str, Atom)> = vec![
+            (
+                "H",
+                Atom {
+                    proton: 1,
+                    neutron: 0,
+                },
+            ),
+            (
+                "H",
+                Atom {
+                    proton: 1,
+                    neutron: 1,
+                },
+            ),
+            (
+                "O",
+                Atom {
+                    proton: 8,
+                    neutron: 8,
+                },
+            ),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(dnec.get_by_name("H").unwrap()[1].1.neutron, 1);
+        assert_eq!(dnec.get_by_name("O").unwrap()[0].1.proton, 8);
+    }
+
+    #[test]
+    fn test_get_by_name() {
+        //---------------------------------------------------------------------------
+        // UNEC
+        //---------------------------------------------------------------------------
+        let mut unec = UNEC::<Atom>::new();
+        for i in 0..10 {
+            unec.push(
+                format!("NAME{}", i),
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        assert_eq!(unec.get_by_name("NAME3").unwrap().proton, 3);
+        assert!(unec.get_by_name("NAME10").is_none());
+
+        unec.get_by_name_mut("NAME3").unwrap().neutron = 30;
+        assert_eq!(unec["NAME3"].elem.neutron, 30);
+        assert!(unec.get_by_name_mut("NAME10").is_none());
+
+        unec.remove(0);
+        assert_eq!(unec.get_by_name("NAME9").unwrap().proton, 9);
+
+        //---------------------------------------------------------------------------
+        // DNEC
+        //---------------------------------------------------------------------------
+        let mut dnec = DNEC::<Atom>::new();
+        for i in 0..12 {
+            dnec.push(
+                ["A", "B", "C"][i % 3],
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        assert_eq!(dnec.first_by_name("B").unwrap().proton, 1);
+        assert_eq!(dnec.last_by_name("B").unwrap().proton, 10);
+        assert_eq!(dnec.nth_by_name("B", 2).unwrap().proton, 7);
+        assert!(dnec.nth_by_name("B", 4).is_none());
+        assert!(dnec.first_by_name("D").is_none());
+        assert!(dnec.last_by_name("D").is_none());
+
+        dnec.first_by_name_mut("C").unwrap().neutron = 100;
+        dnec.nth_by_name_mut("C", 1).unwrap().neutron = 101;
+        dnec.last_by_name_mut("C").unwrap().neutron = 103;
+        assert!(dnec.nth_by_name_mut("C", 4).is_none());
+        assert_eq!(dnec[2].elem.neutron, 100);
+        assert_eq!(dnec[5].elem.neutron, 101);
+        assert_eq!(dnec[8].elem.neutron, 8);
+        assert_eq!(dnec[11].elem.neutron, 103);
+
+        let v = dnec.get_by_name_mut("A").unwrap();
+        assert_eq!(v.len(), 4);
+        for a in v {
+            a.neutron += 1000;
+        }
+        for e in dnec.iter() {
+            if e.name == "A" {
+                assert_eq!(e.elem.neutron, e.elem.proton + 1000);
+            }
+        }
+        assert!(dnec.get_by_name_mut("D").is_none());
+    }
+
+    #[test]
+    fn test_lazy_iterators() {
+        let mut dnec = DNEC::<Atom>::new();
+        for i in 0..12 {
+            dnec.push(
+                ["A", "B", "C"][i % 3],
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        let protons: Vec<_> = dnec.iter_by_name("B").map(|a| a.proton).collect();
+        assert_eq!(protons, vec![1, 4, 7, 10]);
+        let protons: Vec<_> = dnec.iter_by_name("B").rev().map(|a| a.proton).collect();
+        assert_eq!(protons, vec![10, 7, 4, 1]);
+        assert_eq!(dnec.iter_by_name("C").len(), 4);
+        assert_eq!(dnec.iter_by_name("D").count(), 0);
+
+        assert!(dnec.iter_bundles_by_name("A").all(|e| e.name == "A"));
+        assert_eq!(dnec.iter_bundles_by_name("D").count(), 0);
+
+        let mut names: Vec<_> = dnec.iter_names().collect();
+        names.sort();
+        assert_eq!(names, vec!["A", "B", "C"]);
+
+        // wrappers
+        let v: Vec<_> = dnec.iter_by_name("A").collect();
+        assert_eq!(v.len(), dnec.get_by_name("A").unwrap().len());
+        assert_eq!(dnec.names().len(), dnec.iter_names().len());
+
+        // UNEC
+        let unec = UNEC::<Atom>::from(
+            (0..10)
+                .map(|i| {
+                    (
+                        format!("NAME{}", i),
+                        Atom {
+                            proton: i,
+                            neutron: i,
+                        },
+                    )
+                })
+                .collect::<Vec<_>>(),
+        );
+        let v: Vec<_> = unec.iter_by_name("NAME3").collect();
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0].proton, 3);
+        assert_eq!(unec.iter_bundles_by_name("NAME10").count(), 0);
+        assert_eq!(unec.iter_names().count(), 10);
+    }
+
+    #[test]
+    fn test_rename() {
+        //---------------------------------------------------------------------------
+        // UNEC
+        //---------------------------------------------------------------------------
+        let mut unec = UNEC::<Atom>::new();
+        for i in 0..5 {
+            unec.push(
+                format!("NAME{}", i),
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        assert!(unec.rename(2, "NEW2").is_ok());
+        assert_eq!(unec.get_name(2).unwrap(), "NEW2");
+        assert_eq!(unec["NEW2"].elem.proton, 2);
+        assert!(!unec.contains_name("NAME2"));
+
+        // renaming to itself is a no-op
+        assert!(unec.rename(2, "NEW2").is_ok());
+
+        // conflict
+        assert_eq!(
+            unec.rename(2, "NAME3"),
+            Err(NecError::DuplicateName("NAME3".to_string()))
+        );
+        assert_eq!(unec.get_name(2).unwrap(), "NEW2");
+        assert_eq!(unec["NAME3"].elem.proton, 3);
+
+        assert!(unec.rename_by_name("NAME4", "NEW4").is_ok());
+        assert_eq!(unec.get_name(4).unwrap(), "NEW4");
+        assert_eq!(
+            unec.rename_by_name("NAME4", "NEW4"),
+            Err(NecError::NameNotFound("NAME4".to_string()))
+        );
+        assert_eq!(
+            unec.rename_by_name("NAME0", "NAME1"),
+            Err(NecError::DuplicateName("NAME1".to_string()))
+        );
+
+        for (i, e) in unec.iter().enumerate() {
+            assert_eq!(unec.hmap[&e.name], i);
+        }
+        assert_eq!(unec.hmap.len(), 5);
+
+        //---------------------------------------------------------------------------
+        // DNEC
+        //---------------------------------------------------------------------------
+        let mut dnec = DNEC::<Atom>::new();
+        for i in 0..12 {
+            dnec.push(
+                ["A", "B", "C"][i % 3],
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        let protons = |nec: &DNEC<Atom>, name| -> Vec<usize> {
+            nec.iter_by_name(name).map(|a| a.proton).collect()
+        };
+
+        // single occurrence
+        assert!(dnec.rename(4, "C").is_ok());
+        assert_eq!(protons(&dnec, "B"), vec![1, 7, 10]);
+        assert_eq!(protons(&dnec, "C"), vec![2, 4, 5, 8, 11]);
+
+        assert!(dnec.rename_nth_by_name("C", 0, "D").is_ok());
+        assert_eq!(protons(&dnec, "D"), vec![2]);
+        assert!(dnec.rename_nth_by_name("C", 4, "D").is_err());
+
+        // all occurrences, merging with an existing name
+        assert!(dnec.rename_by_name("A", "D").is_ok());
+        assert!(!dnec.contains_name("A"));
+        assert_eq!(protons(&dnec, "D"), vec![0, 2, 3, 6, 9]);
+
+        for (name, indexes) in &dnec.hmap {
+            assert!(indexes.windows(2).all(|w| w[0] < w[1]));
+            for &i in &indexes {
+                assert_eq!(&dnec[i].name, name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bulk() {
+        // every index stored in the hashmap must point to an element of the same name,
+        // and every element must be indexed
+        fn check(nec: &DNEC<Atom>) {
+            let mut count = 0;
+            for (name, indexes) in &nec.hmap {
+                assert!(indexes.windows(2).all(|w| w[0] < w[1]));
+                for &i in &indexes {
+                    assert_eq!(&nec[i].name, name);
+                    count += 1;
+                }
+            }
+            assert_eq!(count, nec.len());
+        }
+
+        let build = || {
+            let mut dnec = DNEC::<Atom>::new();
+            for i in 0..12 {
+                dnec.push(
+                    ["A", "B", "C"][i % 3],
+                    Atom {
+                        proton: i,
+                        neutron: i,
+                    },
+                );
+            }
+            dnec
+        };
+        let protons =
+            |nec: &DNEC<Atom>| -> Vec<usize> { nec.iter().map(|e| e.elem.proton).collect() };
+
+        //---------------------------------------------------------------------------
+        // retain
+        //---------------------------------------------------------------------------
+        let mut dnec = build();
+        dnec.retain(|e| e.name != "B" && e.elem.proton < 9);
+        assert_eq!(protons(&dnec), vec![0, 2, 3, 5, 6, 8]);
+        assert!(!dnec.contains_name("B"));
+        check(&dnec);
+
+        //---------------------------------------------------------------------------
+        // drain
+        //---------------------------------------------------------------------------
+        let mut dnec = build();
+        let drained: Vec<_> = dnec.drain(2..5).map(|e| e.elem.proton).collect();
+        assert_eq!(drained, vec![2, 3, 4]);
+        assert_eq!(protons(&dnec), vec![0, 1, 5, 6, 7, 8, 9, 10, 11]);
+        check(&dnec);
+
+        // not consumed
+        dnec.drain(..);
+        assert!(dnec.is_empty());
+        assert!(dnec.hmap.is_empty());
+
+        //---------------------------------------------------------------------------
+        // truncate
+        //---------------------------------------------------------------------------
+        let mut dnec = build();
+        dnec.truncate(20);
+        assert_eq!(dnec.len(), 12);
+        let h = dnec.push_handle(
+            "D",
+            Atom {
+                proton: 12,
+                neutron: 12,
+            },
+        );
+        assert_eq!(dnec.index_of_handle(h), Some(12));
+        assert_eq!(dnec.handle_of(12), Some(h));
+        // no slot was given to missing elements
+        assert_eq!(h.slot(), 12);
+        dnec.pop();
+        dnec.truncate(4);
+        assert_eq!(protons(&dnec), vec![0, 1, 2, 3]);
+        check(&dnec);
+        dnec.truncate(0);
+        assert!(dnec.hmap.is_empty());
+
+        //---------------------------------------------------------------------------
+        // split_off & append
+        //---------------------------------------------------------------------------
+        let mut dnec = build();
+        let mut other = dnec.split_off(7);
+        assert_eq!(protons(&dnec), vec![0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(protons(&other), vec![7, 8, 9, 10, 11]);
+        check(&dnec);
+        check(&other);
+
+        dnec.append(&mut other);
+        assert!(other.is_empty());
+        assert!(other.hmap.is_empty());
+        assert_eq!(protons(&dnec), (0..12).collect::<Vec<_>>());
+        check(&dnec);
+
+        //---------------------------------------------------------------------------
+        // extend
+        //---------------------------------------------------------------------------
+        let mut dnec = build();
+        dnec.extend((12..15).map(|i| {
+            (
+                "D".to_string(),
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            )
+        }));
+        assert_eq!(dnec.len(), 15);
+        assert_eq!(dnec.get_by_name("D").unwrap().len(), 3);
+        check(&dnec);
+
+        //---------------------------------------------------------------------------
+        // UNEC duplicates
+        //---------------------------------------------------------------------------
+        let mut unec = UNEC::<Atom>::new();
+        unec.extend((0..5).map(|i| {
+            (
+                format!("NAME{}", i),
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            )
+        }));
+        unec.extend((3..8).map(|i| {
+            (
+                format!("NAME{}", i),
+                Atom {
+                    proton: i,
+                    neutron: 100,
+                },
+            )
+        }));
+        assert_eq!(unec.len(), 8);
+        assert_eq!(unec["NAME2"].elem.neutron, 2);
+        assert_eq!(unec["NAME3"].elem.neutron, 100);
+        assert_eq!(unec.get_name(3).unwrap(), "NAME3");
+
+        let mut other = unec.split_off(5);
+        other.push(
+            "NAME0",
+            Atom {
+                proton: 0,
+                neutron: 100,
+            },
+        );
+        unec.append(&mut other);
+        assert_eq!(unec.len(), 8);
+        assert_eq!(unec["NAME0"].elem.neutron, 100);
+        for (i, e) in unec.iter().enumerate() {
+            assert_eq!(unec.hmap[&e.name], i);
+        }
+    }
+
+    #[test]
+    fn test_reorder() {
+        fn check(nec: &DNEC<Atom>) {
+            let mut count = 0;
+            for (name, indexes) in &nec.hmap {
+                assert!(indexes.windows(2).all(|w| w[0] < w[1]));
+                for &i in &indexes {
+                    assert_eq!(&nec[i].name, name);
+                    count += 1;
+                }
+            }
+            assert_eq!(count, nec.len());
+        }
+
+        let mut dnec = DNEC::<Atom>::new();
+        for i in 0..12 {
+            dnec.push(
+                ["C", "B", "A"][i % 3],
+                Atom {
+                    proton: i,
+                    neutron: 11 - i,
+                },
+            );
+        }
+        let protons =
+            |nec: &DNEC<Atom>| -> Vec<usize> { nec.iter().map(|e| e.elem.proton).collect() };
+
+        //---------------------------------------------------------------------------
+        // sorts
+        //---------------------------------------------------------------------------
+        dnec.sort_by_name();
+        assert_eq!(protons(&dnec), vec![2, 5, 8, 11, 1, 4, 7, 10, 0, 3, 6, 9]);
+        check(&dnec);
+
+        dnec.sort_by_key(|a| a.neutron);
+        assert_eq!(protons(&dnec), (0..12).rev().collect::<Vec<_>>());
+        check(&dnec);
+
+        dnec.sort_by(|a, b| a.elem.proton.cmp(&b.elem.proton));
+        assert_eq!(protons(&dnec), (0..12).collect::<Vec<_>>());
+        check(&dnec);
+
+        //---------------------------------------------------------------------------
+        // reverse, swap, move_to, rotate
+        //---------------------------------------------------------------------------
+        dnec.reverse();
+        assert_eq!(protons(&dnec), (0..12).rev().collect::<Vec<_>>());
+        check(&dnec);
+        dnec.reverse();
+
+        dnec.swap(0, 11);
+        assert_eq!(dnec[0].elem.proton, 11);
+        assert_eq!(dnec[11].elem.proton, 0);
+        check(&dnec);
+
+        // same name
+        dnec.swap(1, 4);
+        assert_eq!(dnec[1].elem.proton, 4);
+        check(&dnec);
+        dnec.swap(3, 3);
+        check(&dnec);
+
+        dnec.sort_by(|a, b| a.elem.proton.cmp(&b.elem.proton));
+        dnec.move_to(1, 5);
+        assert_eq!(protons(&dnec), vec![0, 2, 3, 4, 5, 1, 6, 7, 8, 9, 10, 11]);
+        check(&dnec);
+        dnec.move_to(5, 1);
+        assert_eq!(protons(&dnec), (0..12).collect::<Vec<_>>());
+        check(&dnec);
+
+        dnec.rotate_left(3);
+        assert_eq!(protons(&dnec), vec![3, 4, 5, 6, 7, 8, 9, 10, 11, 0, 1, 2]);
+        check(&dnec);
+        dnec.rotate_right(3);
+        assert_eq!(protons(&dnec), (0..12).collect::<Vec<_>>());
+        check(&dnec);
+
+        //---------------------------------------------------------------------------
+        // UNEC
+        //---------------------------------------------------------------------------
+        let mut unec = UNEC::<Atom>::new();
+        for i in 0..10 {
+            unec.push(
+                format!("NAME{}", 9 - i),
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+        unec.sort_by_name();
+        unec.swap(2, 7);
+        unec.move_to(0, 9);
+        unec.rotate_left(4);
+        for (i, e) in unec.iter().enumerate() {
+            assert_eq!(unec.hmap[&e.name], i);
+        }
+        assert_eq!(unec["NAME3"].elem.proton, 6);
+    }
+
+    #[test]
+    fn test_duplicate_policy() {
+        let atom = |proton, neutron| Atom { proton, neutron };
+
+        //---------------------------------------------------------------------------
+        // KeepLast (default)
+        //---------------------------------------------------------------------------
+        let mut unec = UNEC::<Atom>::new();
+        assert!(matches!(unec.duplicate_policy(), DuplicatePolicy::KeepLast));
+        assert!(unec.push("H", atom(1, 0)).is_none());
+        assert!(unec.push("He", atom(2, 2)).is_none());
+        let old = unec.push("H", atom(1, 1)).unwrap();
+        assert_eq!(old.elem.neutron, 0);
+        assert_eq!(unec["H"].elem.neutron, 1);
+        assert_eq!(unec.get_name(0).unwrap(), "H");
+
+        //---------------------------------------------------------------------------
+        // KeepFirst
+        //---------------------------------------------------------------------------
+        unec.set_duplicate_policy(DuplicatePolicy::KeepFirst);
+        let rejected = unec.push("H", atom(1, 2)).unwrap();
+        assert_eq!(rejected.elem.neutron, 2);
+        assert_eq!(unec["H"].elem.neutron, 1);
+        assert!(unec.insert(0, "He", atom(2, 3)).is_some());
+        assert_eq!(unec["He"].elem.neutron, 2);
+
+        //---------------------------------------------------------------------------
+        // Merge
+        //---------------------------------------------------------------------------
+        // closures may capture their environment
+        let factor = 1;
+        unec.set_duplicate_policy(DuplicatePolicy::merge(move |a: &mut Atom, b: Atom| {
+            a.neutron += factor * b.neutron
+        }));
+        assert!(unec.push("H", atom(1, 10)).is_none());
+        assert_eq!(unec["H"].elem.neutron, 11);
+        unec.extend(vec![
+            ("He".to_string(), atom(2, 1)),
+            ("Li".to_string(), atom(3, 4)),
+        ]);
+        assert_eq!(unec["He"].elem.neutron, 3);
+        assert_eq!(unec.len(), 3);
+        unec.extend(vec![
+            ("Be".to_string(), atom(4, 2)),
+            ("Be".to_string(), atom(4, 3)),
+        ]);
+        assert_eq!(unec["Be"].elem.neutron, 5);
+        assert_eq!(unec.len(), 4);
+
+        //---------------------------------------------------------------------------
+        // Error
+        //---------------------------------------------------------------------------
+        let mut unec = UNEC::<Atom>::with_duplicate_policy(DuplicatePolicy::Error);
+        assert!(unec.try_push("H", atom(1, 0)).is_ok());
+        assert_eq!(
+            unec.try_push("H", atom(1, 1)),
+            Err(NecError::DuplicateName("H".to_string()))
+        );
+        assert_eq!(unec.len(), 1);
+        assert_eq!(unec["H"].elem.neutron, 0);
+
+        // per call
+        let old = unec
+            .push_with_policy("H", atom(1, 1), DuplicatePolicy::KeepLast)
+            .unwrap()
+            .unwrap();
+        assert_eq!(old.elem.neutron, 0);
+        assert!(unec
+            .push_with_policy("H", atom(1, 2), DuplicatePolicy::Error)
+            .is_err());
+        assert_eq!(unec["H"].elem.neutron, 1);
+
+        // nothing is moved when a name is already in the collection
+        let mut other = UNEC::<Atom>::new();
+        other.push("Li", atom(3, 4));
+        other.push("H", atom(1, 3));
+        let appended = panic::catch_unwind(panic::AssertUnwindSafe(|| unec.append(&mut other)));
+        assert!(appended.is_err());
+        assert_eq!(other.len(), 2);
+        assert_eq!(unec.len(), 1);
+        let extended = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            unec.extend(vec![
+                ("He".to_string(), atom(2, 2)),
+                ("He".to_string(), atom(2, 3)),
+            ])
+        }));
+        assert!(extended.is_err());
+        assert_eq!(unec.len(), 1);
+
+        // policy is cloned
+        let mut cloned = unec.clone();
+        assert!(matches!(cloned.duplicate_policy(), DuplicatePolicy::Error));
+        assert!(cloned.try_push("H", atom(1, 0)).is_err());
+        let tail = cloned.split_off(0);
+        assert!(matches!(tail.duplicate_policy(), DuplicatePolicy::Error));
+
+        //---------------------------------------------------------------------------
+        // DNEC is not concerned
+        //---------------------------------------------------------------------------
+        let mut dnec = DNEC::<Atom>::with_duplicate_policy(DuplicatePolicy::Error);
+        assert!(dnec.push("H", atom(1, 0)).is_none());
+        assert!(dnec.try_push("H", atom(1, 0)).is_ok());
+        assert_eq!(dnec.len(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_duplicate_policy_error_panics() {
+        let mut unec = UNEC::<Atom>::with_duplicate_policy(DuplicatePolicy::Error);
+        unec.push(
+            "H",
+            Atom {
+                proton: 1,
+                neutron: 0,
+            },
+        );
+        unec.push(
+            "H",
+            Atom {
+                proton: 1,
+                neutron: 0,
+            },
+        );
+    }
+
+    #[test]
+    fn test_entry() {
+        //---------------------------------------------------------------------------
+        // UNEC
+        //---------------------------------------------------------------------------
+        let mut unec = UNEC::<Atom>::new();
+        unec.push(
+            "H",
+            Atom {
+                proton: 1,
+                neutron: 0,
+            },
+        );
+
+        unec.entry("H")
+            .or_insert(Atom {
+                proton: 0,
+                neutron: 0,
+            })
+            .neutron = 1;
+        unec.entry("He")
+            .or_insert(Atom {
+                proton: 2,
+                neutron: 0,
+            })
+            .neutron = 2;
+        unec.entry("Li").or_insert_with(|| Atom {
+            proton: 3,
+            neutron: 4,
+        });
+        unec.entry("Li")
+            .and_modify(|a| a.neutron += 1)
+            .or_insert_with(|| unreachable!());
+        assert_eq!(unec.len(), 3);
+        assert_eq!(unec["H"].elem.neutron, 1);
+        assert_eq!(unec["He"].elem.proton, 2);
+        assert_eq!(unec["Li"].elem.neutron, 5);
+        assert_eq!(unec.get_name(2).unwrap(), "Li");
+
+        match unec.entry("He") {
+            Entry::Occupied(mut e) => {
+                assert_eq!(e.key(), "He");
+                assert_eq!(e.index(), 1);
+                assert_eq!(e.get().proton, 2);
+                e.get_mut().neutron = 3;
+                let old = e.insert(Atom {
+                    proton: 2,
+                    neutron: 2,
+                });
+                assert_eq!(old.neutron, 3);
+                assert_eq!(e.remove().name, "He");
+            }
+            Entry::Vacant(_) => panic!("He should be occupied"),
+        }
+        assert!(!unec.contains_name("He"));
+        assert_eq!(unec["Li"].elem.proton, 3);
+
+        match unec.entry("Be") {
+            Entry::Vacant(e) => assert_eq!(e.into_key(), "Be"),
+            Entry::Occupied(_) => panic!("Be should be vacant"),
+        }
+        assert!(!unec.contains_name("Be"));
+
+        let mut counters = UNEC::<usize>::new();
+        for &name in &["a", "b", "a"] {
+            *counters.entry(name).or_default() += 1;
+        }
+        assert_eq!(counters["a"].elem, 2);
+        assert_eq!(counters["b"].elem, 1);
+
+        //---------------------------------------------------------------------------
+        // DNEC
+        //---------------------------------------------------------------------------
+        let mut dnec = DNEC::<Atom>::new();
+        dnec.push(
+            "H",
+            Atom {
+                proton: 1,
+                neutron: 0,
+            },
+        );
+        dnec.push(
+            "O",
+            Atom {
+                proton: 8,
+                neutron: 8,
+            },
+        );
+
+        match dnec.entry("H") {
+            Entry::Occupied(mut e) => {
+                e.push(Atom {
+                    proton: 1,
+                    neutron: 1,
+                });
+                assert_eq!(e.iter().count(), 2);
+                for a in e.get_all_mut() {
+                    a.neutron += 10;
+                }
+                // first element
+                assert_eq!(e.get().neutron, 10);
+            }
+            Entry::Vacant(_) => panic!("H should be occupied"),
+        }
+        let neutrons: Vec<_> = dnec.iter_by_name("H").map(|a| a.neutron).collect();
+        assert_eq!(neutrons, vec![10, 11]);
+        assert_eq!(dnec.hmap.get("H").unwrap(), vec![0, 2]);
+
+        dnec.entry("N").or_insert(Atom {
+            proton: 7,
+            neutron: 7,
+        });
+        assert_eq!(dnec.hmap.get("N").unwrap(), vec![3]);
+    }
+
+    #[test]
+    fn test_generic_key() {
+        // atoms named by their atomic number
+        let mut unec = UNEC::<Atom, usize>::new();
+        for i in 1..6 {
+            unec.push(
+                i,
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+        assert_eq!(unec[&3].elem.proton, 3);
+        assert_eq!(unec.get_by_name(&5).unwrap().neutron, 5);
+        assert!(!unec.contains_name(&6));
+        assert_eq!(unec.rename(0, 2usize), Err(NecError::DuplicateName(2)));
+        assert!(unec.rename(0, 6usize).is_ok());
+        assert_eq!(unec.get_name(0), Some(&6));
+        assert_eq!(unec.remove_by_name(&6).unwrap().elem.proton, 1);
+
+        // Box<str> keys can be looked up with a &str
+        let mut dnec = DNEC::<Atom, Box<str>>::new();
+        for name in &["H", "O", "H"] {
+            dnec.push(
+                *name,
+                Atom {
+                    proton: 1,
+                    neutron: 0,
+                },
+            );
+        }
+        assert_eq!(dnec.get_by_name("H").unwrap().len(), 2);
+        assert_eq!(dnec.hmap.get("H").unwrap(), vec![0, 2]);
+        assert!(dnec.rename_by_name("H", "D").is_ok());
+        assert_eq!(&*dnec[2].name, "D");
+    }
+
+    #[test]
+    fn test_hasher() {
+        type Fixed = BuildHasherDefault<DefaultHasher>;
+
+        // a fixed-seed hasher gives the same names order for the same pushes
+        let mut names = Vec::new();
+        for _ in 0..2 {
+            let mut dnec =
+                DNEC::<Atom, String, Fixed>::with_capacity_and_hasher(20, Fixed::default());
+            for i in 0..20 {
+                dnec.push(
+                    format!("NAME{}", i % 7),
+                    Atom {
+                        proton: i,
+                        neutron: i,
+                    },
+                );
+            }
+            assert!(dnec.list.capacity() >= 20);
+            assert_eq!(dnec.get_by_name("NAME3").unwrap().len(), 3);
+            names.push(dnec.names());
+        }
+        assert_eq!(names[0], names[1]);
+
+        let mut unec = UNEC::<Atom, String, Fixed>::with_hasher(Fixed::default());
+        for i in 0..10 {
+            unec.push(
+                format!("NAME{}", i),
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        // derived collections keep the hasher
+        let other: UNEC<Atom, String, Fixed> = unec.split_off(5);
+        assert_eq!(other["NAME5"].elem.proton, 5);
+        let cloned = unec.clone();
+        assert_eq!(cloned.hmap["NAME4"], 4);
+        let collected: UNEC<Atom, String, Fixed> = vec![(
+            "H".to_string(),
+            Atom {
+                proton: 1,
+                neutron: 0,
+            },
+        )]
+        .into();
+        assert_eq!(collected.len(), 1);
+    }
+
+    #[test]
+    fn test_normalizer() {
+        let mut unec = UNEC::<Atom, String, RandomState, _>::with_normalizer((
+            Trim,
+            (AsciiCaseFold, FoldSeparators),
+        ));
+        unec.push(
+            "Heavy_Hydrogen",
+            Atom {
+                proton: 1,
+                neutron: 1,
+            },
+        );
+        unec.push(
+            "Helium",
+            Atom {
+                proton: 2,
+                neutron: 2,
+            },
+        );
+
+        // same name, the element is replaced but keeps its first spelling
+        let old = unec
+            .push(
+                " heavy-hydrogen ",
+                Atom {
+                    proton: 1,
+                    neutron: 2,
+                },
+            )
+            .unwrap();
+        assert_eq!(old.elem.neutron, 1);
+        assert_eq!(unec.len(), 2);
+        assert_eq!(unec.get_name(0).unwrap(), "Heavy_Hydrogen");
+        assert_eq!(unec["HEAVY-HYDROGEN"].elem.neutron, 2);
+        assert!(unec.contains_name("heavy_hydrogen"));
+        assert_eq!(unec.hmap["heavy-hydrogen"], 0);
+
+        // renaming to another spelling of the same name only changes the spelling
+        assert!(unec.rename(1, "HELIUM").is_ok());
+        assert_eq!(unec.get_name(1).unwrap(), "HELIUM");
+        assert!(unec.rename(1, "Heavy-Hydrogen").is_err());
+
+        *unec.entry("helium").or_insert(Atom {
+            proton: 0,
+            neutron: 0,
+        }) = Atom {
+            proton: 2,
+            neutron: 1,
+        };
+        assert_eq!(unec.len(), 2);
+        assert_eq!(unec["Helium"].elem.neutron, 1);
+        assert_eq!(unec.remove_by_name("helium ").unwrap().name, "HELIUM");
+
+        let mut dnec = DNEC::<Atom, String, RandomState, _>::with_normalizer(AsciiCaseFold);
+        for name in &["H", "h", "O", "H"] {
+            dnec.push(
+                *name,
+                Atom {
+                    proton: 1,
+                    neutron: 0,
+                },
+            );
+        }
+        assert_eq!(dnec.hmap.get("h").unwrap(), vec![0, 1, 3]);
+        assert_eq!(dnec.get_name(1).unwrap(), "h");
+        let mut other = dnec.split_off(2);
+        assert_eq!(other.get_by_name("o").unwrap().len(), 1);
+        assert!(other.rename_by_name("h", "Hydrogen").is_ok());
+        assert_eq!(other.get_name(1).unwrap(), "Hydrogen");
+        assert_eq!(other.remove_all_by_name("HYDROGEN").len(), 1);
+    }
+
+    #[test]
+    fn test_sorted() {
+        let mut dnec = SortedDNEC::<Atom>::new();
+        for (i, name) in ["C", "He", "H", "O", "H", "Li", "He"].iter().enumerate() {
+            dnec.push(
+                *name,
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        let sorted: Vec<_> = dnec.iter_sorted().map(|e| e.elem.proton).collect();
+        assert_eq!(sorted, vec![0, 2, 4, 1, 6, 5, 3]);
+        assert_eq!(dnec.names(), vec!["C", "H", "He", "Li", "O"]);
+        assert_eq!(dnec.names_with_prefix("H").count(), 2);
+        assert_eq!(dnec.range_by_name("D".."Li").count(), 4);
+        assert_eq!(dnec.range_by_name("D"..="Li").count(), 5);
+
+        // indexes are kept up to date by the other operations
+        dnec.remove(0);
+        dnec.insert(
+            0,
+            "Be",
+            Atom {
+                proton: 9,
+                neutron: 9,
+            },
+        );
+        dnec.remove_all_by_name("He");
+        assert_eq!(dnec.hmap["H"], vec![1, 3]);
+        assert_eq!(dnec.names(), vec!["Be", "H", "Li", "O"]);
+        let sorted: Vec<_> = dnec.iter_sorted().map(|e| e.elem.proton).collect();
+        assert_eq!(sorted, vec![9, 2, 4, 5, 3]);
+
+        let mut unec = SortedUNEC::<Atom, String, AsciiCaseFold>::new();
+        for (i, name) in ["server.port", "Client.Port", "SERVER.host"]
+            .iter()
+            .enumerate()
+        {
+            unec.push(
+                *name,
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+        let names: Vec<&String> = unec.names_with_prefix("Server.").collect();
+        assert_eq!(names, vec!["server.host", "server.port"]);
+        assert_eq!(
+            unec.range_by_name(.."D").next().unwrap().name,
+            "Client.Port"
+        );
+        assert_eq!(unec["client.port"].elem.proton, 1);
+        assert!(unec.names_with_prefix("z").next().is_none());
+
+        // integer names
+        let mut numbers = SortedUNEC::<Atom, usize>::new();
+        for i in (0..10).rev() {
+            numbers.push(
+                i,
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+        let range: Vec<_> = numbers.range_by_name(&3..&6).map(|e| e.name).collect();
+        assert_eq!(range, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_glob() {
+        let mut dnec = DNEC::<Atom>::new();
+        let names = [
+            "sensor.1.temp",
+            "sensor.2.temp",
+            "sensor.10.temp",
+            "sensor.1.humidity",
+            "[raw]",
+            "sensor.2.temp",
+        ];
+        for (i, &name) in names.iter().enumerate() {
+            dnec.push(
+                name,
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+
+        let found: Vec<usize> = dnec.find_glob("sensor.*.temp").map(|(i, _)| i).collect();
+        assert_eq!(found, vec![0, 1, 2, 5]);
+        assert_eq!(dnec.count_glob("sensor.?.temp"), 3);
+        assert_eq!(dnec.count_glob("sensor.[12].*"), 4);
+        assert_eq!(dnec.count_glob("sensor.[!2]*"), 3);
+        assert_eq!(dnec.count_glob("sensor.[0-9][0-9].temp"), 1);
+        assert_eq!(dnec.count_glob("[raw]"), 0);
+        assert_eq!(dnec.count_glob("[raw"), 0);
+        assert_eq!(dnec.count_glob("[[]raw]"), 1);
+        assert_eq!(dnec.count_glob("*"), 6);
+        assert_eq!(dnec.count_glob(""), 0);
+        assert_eq!(dnec.count_glob("sensor.*.*.temp"), 0);
+
+        let removed = dnec.remove_glob("sensor.2.*");
+        assert_eq!(removed.len(), 2);
+        assert_eq!(removed[1].elem.proton, 5);
+        assert_eq!(dnec.len(), 4);
+        assert!(dnec.get_by_name("sensor.2.temp").is_none());
+        assert_eq!(dnec.get_by_name("sensor.1.humidity").unwrap()[0].proton, 3);
+
+        let mut unec = UNEC::<Atom, String, RandomState, AsciiCaseFold>::new();
+        for (i, &name) in ["Sensor.A", "sensor.b", "Pump.A"].iter().enumerate() {
+            unec.push(
+                name,
+                Atom {
+                    proton: i,
+                    neutron: i,
+                },
+            );
+        }
+        assert_eq!(unec.count_glob("SENSOR.?"), 2);
+        assert_eq!(unec.remove_glob("*.a").len(), 2);
+        assert_eq!(unec["SENSOR.B"].elem.proton, 1);
+    }
+
+    #[test]
+    fn test_aliases() {
+        let atom = |proton| Atom { proton, neutron: 0 };
+        let mut dnec = DNEC::<Atom>::with_alias_format(hash_suffix);
+        for (i, &name) in ["H", "O", "H", "H", "O"].iter().enumerate() {
+            dnec.push(name, atom(i));
+        }
+        let aliases: Vec<_> = dnec.iter().map(|e| e.alias.clone().unwrap()).collect();
+        assert_eq!(aliases, vec!["H#0", "O#0", "H#1", "H#2", "O#1"]);
+
+        // aliases follow their elements
+        dnec.remove(0);
+        assert_eq!(dnec.get_by_alias("H#1").unwrap().proton, 2);
+        dnec.swap_remove(0);
+        assert_eq!(dnec.index_of_alias("O#1"), Some(0));
+        assert_eq!(dnec.index_of_alias("H#2"), Some(2));
+        assert!(dnec.index_of_alias("O#0").is_none());
+        dnec.insert(0, "O", atom(5));
+        assert_eq!(dnec[0].alias.as_ref().unwrap(), "O#2");
+        assert_eq!(dnec.index_of_alias("H#2"), Some(3));
+        dnec.sort_by_key(|a| a.proton);
+        assert_eq!(dnec.index_of_alias("H#1"), Some(0));
+        assert_eq!(dnec.index_of_alias("O#2"), Some(3));
+
+        // used numbers are skipped
+        dnec.push("H", atom(6));
+        assert_eq!(dnec.index_of_alias("H#3"), Some(4));
+        dnec.entry("H").or_insert(atom(7));
+        dnec.remove_all_by_name("O");
+        assert_eq!(dnec.len(), 3);
+        assert_eq!(dnec.index_of_alias("H#2"), Some(1));
+        assert!(dnec.get_by_alias("O#1").is_none());
+
+        // renamed elements keep their aliases
+        assert!(dnec.rename(0, "D").is_ok());
+        assert_eq!(dnec.get_by_alias("H#1").unwrap().proton, 2);
+        dnec.push("D", atom(8));
+        assert_eq!(dnec[3].alias.as_ref().unwrap(), "D#1");
+
+        let other = dnec.split_off(2);
+        assert_eq!(other.index_of_alias("D#1"), Some(1));
+        assert!(dnec.get_by_alias("D#1").is_none());
+        let mut cloned = dnec.clone();
+        assert_eq!(cloned.pop().unwrap().alias.unwrap(), "H#2");
+        assert!(cloned.get_by_alias("H#2").is_none());
+        assert_eq!(dnec.index_of_alias("H#2"), Some(1));
+
+        // elements added together don't share the numbers they skip
+        let mut dnec = DNEC::<Atom>::with_alias_format(hash_suffix);
+        dnec.push("H", atom(0));
+        dnec.push("H", atom(1));
+        dnec.remove(0);
+        dnec.extend(vec![("H".to_string(), atom(2)), ("H".to_string(), atom(3))]);
+        let aliases: Vec<_> = dnec.iter().map(|e| e.alias.clone().unwrap()).collect();
+        assert_eq!(aliases, vec!["H#1", "H#2", "H#3"]);
+
+        // aliases given afterwards, with normalized lookups
+        let mut sorted = SortedDNEC::<Atom, String, AsciiCaseFold>::new();
+        for (i, &name) in ["b", "A", "B"].iter().enumerate() {
+            sorted.push(name, atom(i));
+        }
+        assert!(sorted.alias_format().is_none());
+        assert!(sorted.get_by_alias("b-0").is_none());
+        sorted.set_alias_format(|name, n| format!("{}-{}", name, n));
+        assert_eq!(sorted.get_by_alias("B-1").unwrap().proton, 2);
+        assert_eq!(sorted.remove_by_alias("a-0").unwrap().name, "A");
+        sorted.truncate(1);
+        sorted.push("B", atom(3));
+        assert_eq!(sorted[1].alias.as_ref().unwrap(), "B-1");
+        assert_eq!(sorted.iter_sorted().count(), 2);
+    }
+
+    #[test]
+    fn test_extra_aliases() {
+        let atom = |proton| Atom { proton, neutron: 0 };
+        let mut unec = UNEC::<Atom>::new();
+        for (i, &name) in ["sensor-001", "sensor-002", "sensor-003"]
+            .iter()
+            .enumerate()
+        {
+            unec.push(name, atom(i));
+        }
+        assert!(unec.add_alias(1, "kitchen").is_ok());
+        assert!(unec.add_alias(1, "K").is_ok());
+        assert!(unec.add_alias(2, "garage").is_ok());
+        assert_eq!(unec.aliases_of(1).unwrap(), vec!["kitchen", "K"]);
+        assert!(unec.aliases_of(0).unwrap().is_empty());
+
+        // collisions
+        assert_eq!(
+            unec.add_alias(0, "K"),
+            Err(NecError::DuplicateName("K".to_string()))
+        );
+        assert!(unec.add_alias(0, "sensor-003").is_err());
+        assert!(unec.rename(0, "garage").is_err());
+        assert!(unec.try_push("kitchen", atom(9)).is_err());
+        let old = unec.push("kitchen", atom(10)).unwrap();
+        assert_eq!(old.elem.proton, 1);
+        assert_eq!(unec.len(), 3);
+        assert_eq!(unec["K"].elem.proton, 10);
+        assert_eq!(unec[1].name, "sensor-002");
+        assert_eq!(unec.aliases_of(1).unwrap().len(), 2);
+
+        // aliases follow moves and removals
+        unec.insert(0, "sensor-000", atom(11));
+        assert_eq!(unec.index_of_alias("kitchen"), Some(2));
+        unec.swap_remove(0);
+        assert_eq!(unec.index_of_alias("garage"), Some(0));
+        unec.reverse();
+        assert_eq!(unec.index_of_alias("garage"), Some(2));
+        assert_eq!(unec.get_by_name("garage").unwrap().proton, 2);
+        let removed = unec.remove_by_name("K").unwrap();
+        assert_eq!(removed.aliases, vec!["kitchen", "K"]);
+        assert!(!unec.contains_name("kitchen"));
+        assert_eq!(unec.index_of_alias("garage"), Some(1));
+        assert!(unec.add_alias(0, "kitchen").is_ok());
+        unec.pop();
+        assert!(unec.get_by_name("garage").is_none());
+        assert_eq!(
+            unec.remove_alias("Kitchen").unwrap_err(),
+            NecError::NameNotFound("Kitchen".to_string())
+        );
+        assert_eq!(unec.remove_alias("kitchen"), Ok(0));
+        assert!(unec.aliases_of(0).unwrap().is_empty());
+
+        // appended elements keep their extra aliases, unless already used
+        assert!(unec.add_alias(0, "kitchen").is_ok());
+        let name = unec[0].name.clone();
+        let mut other = UNEC::<Atom>::new();
+        other.push("sensor-004", atom(4));
+        other.push("sensor-005", atom(5));
+        assert!(other.add_alias(0, "cellar").is_ok());
+        assert!(other.add_alias(0, name.as_str()).is_ok());
+        assert!(other.add_alias(1, "kitchen").is_ok());
+        unec.append(&mut other);
+        assert_eq!(unec.len(), 3);
+        assert_eq!(unec.aliases_of(1).unwrap(), vec!["cellar"]);
+        assert!(unec.aliases_of(2).unwrap().is_empty());
+        assert_eq!(unec["cellar"].name, "sensor-004");
+        assert_eq!(unec.index_of_alias("kitchen"), Some(0));
+
+        // DNEC with both kinds of aliases, looked up by name
+        let mut dnec = DNEC::<Atom>::with_alias_format(hash_suffix);
+        for (i, &name) in ["H", "O", "H"].iter().enumerate() {
+            dnec.push(name, atom(i));
+        }
+        assert!(dnec.add_alias(2, "deuterium").is_ok());
+        assert!(dnec.add_alias(0, "H#1").is_err());
+        assert!(dnec.add_alias(0, "O").is_ok());
+        assert_eq!(dnec.get_by_name("O").unwrap()[0].proton, 1);
+        assert_eq!(dnec.get_by_name("deuterium").unwrap()[0].proton, 2);
+        assert_eq!(dnec.get_by_name("H#1").unwrap().len(), 1);
+        assert_eq!(dnec.aliases_of(2).unwrap(), vec!["H#1", "deuterium"]);
+        dnec.remove(0);
+        assert_eq!(dnec.index_of_alias("deuterium"), Some(1));
+        assert_eq!(dnec.index_of_alias("H#1"), Some(1));
+        assert_eq!(dnec.remove_alias("H#1"), Ok(1));
+        assert!(dnec[1].alias.is_none());
+        dnec.remove_all_by_name("H");
+        assert!(dnec.index_of_alias("deuterium").is_none());
+        assert_eq!(dnec.names(), vec!["O"]);
+    }
+
+    #[test]
+    fn test_secondary_indexes() {
+        let neutrons = |v: Result<Vec<&Atom>, NecError>| -> Vec<usize> {
+            v.unwrap().iter().map(|a| a.neutron).collect()
+        };
+        let mut dnec = DNEC::<Atom>::new();
+        dnec.push(
+            "H",
+            Atom {
+                proton: 1,
+                neutron: 0,
+            },
+        );
+        dnec.push(
+            "O",
+            Atom {
+                proton: 8,
+                neutron: 1,
+            },
+        );
+        dnec.add_index("by_proton", |a: &Atom| a.proton);
+        dnec.add_index("heavy", |a: &Atom| a.proton > 2);
+        dnec.push(
+            "H",
+            Atom {
+                proton: 1,
+                neutron: 2,
+            },
+        );
+        dnec.insert(
+            0,
+            "He",
+            Atom {
+                proton: 2,
+                neutron: 3,
+            },
+        );
+        assert_eq!(
+            neutrons(dnec.get_by_index("by_proton", &1_usize)),
+            vec![0, 2]
+        );
+        assert_eq!(neutrons(dnec.get_by_index("heavy", &true)), vec![1]);
+        assert_eq!(
+            dnec.get_by_index("by_mass", &1_usize).err(),
+            Some(NecError::IndexNotFound("by_mass".to_string()))
+        );
+        assert_eq!(
+            dnec.get_by_index("by_proton", &1_u8).err(),
+            Some(NecError::WrongKeyType("by_proton".to_string()))
+        );
+
+        // removals and moves
+        dnec.swap(1, 3);
+        assert_eq!(
+            neutrons(dnec.get_by_index("by_proton", &1_usize)),
+            vec![2, 0]
+        );
+        dnec.remove(0);
+        dnec.swap_remove(0);
+        assert_eq!(neutrons(dnec.get_by_index("by_proton", &1_usize)), vec![0]);
+        assert_eq!(neutrons(dnec.get_by_index("by_proton", &8_usize)), vec![1]);
+        assert!(dnec.get_by_index("by_proton", &2_usize).unwrap().is_empty());
+
+        // writes through mutable borrows
+        dnec[0].elem.proton = 8;
+        assert_eq!(
+            neutrons(dnec.get_by_index("by_proton", &8_usize)),
+            vec![0, 1]
+        );
+        dnec.push(
+            "H",
+            Atom {
+                proton: 1,
+                neutron: 4,
+            },
+        );
+        assert_eq!(
+            neutrons(dnec.get_by_index("by_proton", &8_usize)),
+            vec![0, 1]
+        );
+        for bundle in dnec.iter_mut() {
+            bundle.elem.proton += 1;
+        }
+        dnec.pop();
+        assert_eq!(
+            neutrons(dnec.get_by_index("by_proton", &9_usize)),
+            vec![0, 1]
+        );
+        dnec.get_indexed_mut(1).unwrap().proton = 1;
+        assert_eq!(neutrons(dnec.get_by_index("by_proton", &1_usize)), vec![1]);
+        assert_eq!(neutrons(dnec.get_by_index("heavy", &true)), vec![0]);
+
+        // indexes follow clones, split and appends
+        let mut other = dnec.clone();
+        let tail = other.split_off(1);
+        assert_eq!(neutrons(tail.get_by_index("by_proton", &1_usize)), vec![1]);
+        dnec.append(&mut other);
+        assert!(other
+            .get_by_index("by_proton", &9_usize)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            neutrons(dnec.get_by_index("by_proton", &9_usize)),
+            vec![0, 0]
+        );
+        dnec.remove_all_by_name("O");
+        assert!(dnec.get_by_index("by_proton", &1_usize).unwrap().is_empty());
+        assert_eq!(neutrons(dnec.get_by_index("heavy", &true)), vec![0, 0]);
+        assert!(dnec.remove_index("heavy"));
+        dnec.clear();
+        assert!(dnec.get_by_index("by_proton", &1_usize).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_handles() {
+        let atom = |proton| Atom { proton, neutron: 0 };
+        let mut dnec = DNEC::<Atom>::new();
+        let handles: Vec<_> = (0..6)
+            .map(|i| dnec.push_handle(if i % 2 == 0 { "H" } else { "O" }, atom(i)))
+            .collect();
+        assert_eq!(dnec.handle_of(3), Some(handles[3]));
+        assert!(dnec.handle_of(6).is_none());
+
+        // moves keep handles
+        dnec.insert(0, "He", atom(10));
+        dnec.swap(1, 6);
+        dnec.sort_by_name();
+        dnec.rotate_left(2);
+        dnec.move_to(0, 4);
+        for (i, &h) in handles.iter().enumerate() {
+            let index = dnec.index_of_handle(h).unwrap();
+            assert_eq!(dnec[index].elem.proton, i);
+            assert_eq!(dnec.handle_of(index), Some(h));
+        }
+        dnec.swap(0, 6);
+        dnec.insert(3, "He", atom(11));
+        for (i, &h) in handles.iter().enumerate() {
+            assert_eq!(dnec.get_by_handle(h).unwrap().proton, i);
+        }
+
+        // removals make handles stale, and slots are reused with new generations
+        let index = dnec.index_of_handle(handles[2]).unwrap();
+        dnec.remove(index);
+        let index = dnec.index_of_handle(handles[5]).unwrap();
+        dnec.swap_remove(index);
+        dnec.retain(|e| e.elem.proton != 1);
+        let drained: Vec<_> = dnec.drain(..1).collect();
+        let h = dnec.push_handle("N", atom(7));
+        assert!(dnec.get_by_handle(handles[2]).is_none());
+        assert!(dnec.get_by_handle(handles[5]).is_none());
+        assert!(dnec.get_by_handle(handles[1]).is_none());
+        assert_ne!(h, handles[2]);
+        let alive: Vec<_> = handles
+            .iter()
+            .filter_map(|&h| dnec.get_by_handle(h))
+            .map(|a| a.proton)
+            .collect();
+        assert_eq!(
+            alive.len() + drained.iter().filter(|e| e.name != "He").count(),
+            3
+        );
+        dnec.get_by_handle_mut(h).unwrap().neutron = 7;
+        assert_eq!(dnec.get_by_name("N").unwrap()[0].neutron, 7);
+
+        // bulk removals
+        dnec.remove_all_by_name("H");
+        assert!(dnec.get_by_handle(handles[0]).is_none());
+        let mut tail = dnec.split_off(dnec.index_of_handle(h).unwrap());
+        assert!(dnec.get_by_handle(h).is_none());
+        assert_eq!(
+            tail.get_by_handle(tail.handle_of(0).unwrap())
+                .unwrap()
+                .neutron,
+            7
+        );
+        dnec.append(&mut tail);
+        let last = dnec.handle_of(dnec.len() - 1).unwrap();
+        assert_eq!(dnec.get_by_handle(last).unwrap().neutron, 7);
+        assert!(tail.is_empty());
+
+        // replacing an element of a UNEC gives it a new handle, merging keeps it
+        let mut unec = UNEC::<Atom>::new();
+        let h = unec.push_handle("H", atom(1));
+        let h2 = unec.push_handle("H", atom(2));
+        assert_ne!(h, h2);
+        assert!(unec.get_by_handle(h).is_none());
+        unec.set_duplicate_policy(DuplicatePolicy::merge(|a: &mut Atom, b: Atom| {
+            a.proton += b.proton
+        }));
+        assert_eq!(unec.push_handle("H", atom(3)), h2);
+        assert_eq!(unec.get_by_handle(h2).unwrap().proton, 5);
+
+        // other ways to add an element give its handle too
+        let he = unec.insert_handle(0, "He", atom(2));
+        assert_eq!(unec.insert_handle(0, "He", atom(4)), he);
+        let li = unec.try_push("Li", atom(3)).unwrap();
+        assert!(unec.try_push("Li", atom(6)).is_err());
+        assert_eq!(unec.index_of_handle(he), Some(0));
+        assert_eq!(unec.index_of_handle(li), Some(2));
+        assert_eq!(unec.get_by_handle(he).unwrap().proton, 6);
+    }
+
+    #[test]
+    fn test_tombstones() {
+        let atom = |proton| Atom { proton, neutron: 0 };
+        let mut dnec = DNEC::<Atom>::with_alias_format(hash_suffix);
+        dnec.add_index("by_proton", |a: &Atom| a.proton);
+        for i in 0..10 {
+            dnec.push(if i % 3 == 0 { "H" } else { "O" }, atom(i));
+        }
+        let h = dnec.handle_of(5).unwrap();
+
+        // tombstones are skipped by lookups and iterations
+        assert_eq!(dnec.tombstone_by_name("H"), 4);
+        assert!(dnec.tombstone(1));
+        assert!(!dnec.tombstone(0));
+        assert!(!dnec.tombstone(10));
+        assert_eq!(dnec.len(), 10);
+        assert_eq!(dnec.live_len(), 5);
+        assert_eq!(dnec.tombstones(), 5);
+        assert_eq!(dnec.fragmentation(), 0.5);
+        assert!(!dnec.contains_name("H"));
+        assert!(dnec.get(3).is_none());
+        assert!(dnec.get_by_alias("O#0").is_none());
+        assert_eq!(dnec.get_by_alias("O#1").unwrap().proton, 2);
+        assert!(dnec.get_by_index("by_proton", &3_usize).unwrap().is_empty());
+        let protons: Vec<_> = dnec.iter().map(|e| e.elem.proton).collect();
+        assert_eq!(protons, vec![2, 4, 5, 7, 8]);
+        for (i, e) in dnec.iter_indexed() {
+            assert_eq!(i, e.elem.proton);
+            assert_eq!(dnec[i].elem.proton, i);
+        }
+        assert_eq!(dnec.count_glob("*"), 5);
+        assert_eq!(dnec.get_by_handle(h).unwrap().proton, 5);
+
+        // mutable accesses and pushes keep positions
+        for e in dnec.iter_mut() {
+            e.elem.neutron = 1;
+        }
+        dnec.push("N", atom(10));
+        assert_eq!(dnec[10].elem.proton, 10);
+        assert_eq!(dnec[2].elem.neutron, 1);
+        assert_eq!(
+            dnec.get_by_index("by_proton", &10_usize).unwrap()[0].proton,
+            10
+        );
+
+        // moving elements keeps tombstones in place
+        let mut other = dnec.clone();
+        other.remove(4);
+        assert_eq!(other.tombstones(), 5);
+        assert_eq!(other[4].elem.proton, 5);
+        other.insert(0, "He", atom(11));
+        assert_eq!(other.index_of_handle(h), Some(5));
+        assert!(other.get(1).is_none());
+        other.swap(3, 10);
+        assert_eq!(other[3].elem.proton, 10);
+        assert_eq!(other[10].elem.proton, 2);
+        assert_eq!(other.get_by_name("N").unwrap()[0].proton, 10);
+        let mut other = dnec.clone();
+        other.tombstone(10);
+        assert_eq!(other.swap_remove(2).elem.proton, 2);
+        assert!(other.get(2).is_none());
+        assert_eq!((other.len(), other.tombstones()), (10, 6));
+        assert_eq!(other.get_by_name("O").unwrap().len(), 4);
+        assert_eq!(other.pop().unwrap().elem.proton, 8);
+        assert_eq!(other.len(), 8);
+        let tail = other.split_off(4);
+        assert_eq!(tail.len(), 3);
+        assert_eq!(tail.get_by_name("O").unwrap()[0].proton, 4);
+        let drained: Vec<_> = dnec.clone().drain(..5).map(|e| e.elem.proton).collect();
+        assert_eq!(drained, vec![2, 4]);
+        let consumed: Vec<_> = dnec.clone().into_iter().map(|e| e.elem.proton).collect();
+        assert_eq!(consumed, vec![2, 4, 5, 7, 8, 10]);
+
+        // compaction keeps handles, aliases and indexes
+        dnec.compact();
+        assert_eq!(dnec.list.len(), 6);
+        assert_eq!(dnec.fragmentation(), 0.0);
+        assert_eq!(dnec.index_of_handle(h), Some(2));
+        assert_eq!(dnec.index_of_alias("O#1"), Some(0));
+        assert_eq!(
+            dnec.get_by_index("by_proton", &8_usize).unwrap()[0].neutron,
+            1
+        );
+        assert_eq!(dnec.get_by_name("O").unwrap().len(), 5);
+
+        // removing by name keeps the positions of tombstones
+        let mut dnec = DNEC::<usize>::new();
+        let mut vnec = VecDNEC::<usize>::new();
+        for (i, name) in ["A", "B", "C", "B", "C"].iter().enumerate() {
+            dnec.push(*name, i);
+            vnec.push(*name, i);
+        }
+        dnec.tombstone(0);
+        vnec.tombstone(0);
+        assert_eq!(dnec.remove_all_by_name("B").len(), 2);
+        assert_eq!(vnec.remove_all_by_name("B").len(), 2);
+        assert_eq!(dnec.get_by_name("C").unwrap(), vec![&2, &4]);
+        assert_eq!(vnec.get_by_name("C").unwrap(), vec![&2, &4]);
+        assert_eq!((dnec.len(), &dnec[1].name), (3, &"C".to_string()));
+        assert_eq!((vnec.len(), &vnec[1].name), (3, &"C".to_string()));
+    }
+
+    #[test]
+    fn test_offset_dnec() {
+        let atom = |i| Atom {
+            proton: i,
+            neutron: 0,
+        };
+        let mut dnec = DNEC::<Atom>::with_alias_format(hash_suffix);
+        let mut vnec = VecDNEC::<Atom>::with_alias_format(hash_suffix);
+
+        // same operations on both collections, which must stay identical
+        let mut seed = 7_usize;
+        for i in 0..2000 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let r = seed >> 33;
+            let name = format!("N{}", r % 13);
+            let len = dnec.len();
+
+            match r % 8 {
+                0..=2 => {
+                    dnec.push(name.as_str(), atom(i));
+                    vnec.push(name.as_str(), atom(i));
+                }
+                3 => {
+                    let index = r % (len + 1);
+                    assert!(dnec.insert(index, name.as_str(), atom(i)).is_none());
+                    assert!(vnec.insert(index, name.as_str(), atom(i)).is_none());
+                }
+                4 if len > 0 => {
+                    let index = r % len;
+                    assert_eq!(
+                        dnec.remove(index).elem.proton,
+                        vnec.remove(index).elem.proton
+                    );
+                }
+                5 if len > 0 => {
+                    let index = r % len;
+                    dnec.swap_remove(index);
+                    vnec.swap_remove(index);
+                }
+                6 => {
+                    assert_eq!(
+                        dnec.remove_all_by_name(name.as_str()).len(),
+                        vnec.remove_all_by_name(name.as_str()).len()
+                    );
+                }
+                7 if len > 1 => {
+                    dnec.swap(0, len - 1);
+                    vnec.swap(0, len - 1);
+                    dnec.rename(r % len, name.as_str()).unwrap();
+                    vnec.rename(r % len, name.as_str()).unwrap();
+                }
+                _ => {
+                    dnec.sort_by_name();
+                    vnec.sort_by_name();
+                }
+            }
+
+            assert_eq!(dnec.len(), vnec.len());
+            for n in 0..13 {
+                let name = format!("N{}", n);
+                let d: Vec<_> = dnec.iter_by_name(name.as_str()).map(|e| e.proton).collect();
+                let o: Vec<_> = vnec.iter_by_name(name.as_str()).map(|e| e.proton).collect();
+                assert_eq!(d, o);
+            }
+        }
+
+        // aliases follow elements too
+        for i in 0..dnec.len() {
+            let alias = dnec.aliases_of(i).unwrap()[0].clone();
+            assert_eq!(dnec.index_of_alias(&alias), Some(i));
+            assert_eq!(vnec.index_of_alias(&alias), Some(i));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use serde_json;
+        use serialize::{as_map, AsList, AsMap};
+
+        // UNEC is an ordered map, and duplicates are resolved by the policy when decoding
+        let mut unec = UNEC::<usize>::new();
+        unec.push("O", 8);
+        unec.push("H", 1);
+        let json = serde_json::to_string(&unec).unwrap();
+        assert_eq!(json, r#"{"O":8,"H":1}"#);
+        let unec: UNEC<usize> = serde_json::from_str(r#"{"O":8,"H":1,"O":16}"#).unwrap();
+        assert_eq!(unec.len(), 2);
+        assert_eq!(unec["O"].elem, 16);
+        assert_eq!(unec.get_name(0).unwrap(), "O");
+        let unec: SortedUNEC<usize> = serde_json::from_str(&json).unwrap();
+        assert_eq!(unec.iter_sorted().next().unwrap().elem, 1);
+        let list: AsList<UNEC<usize>> =
+            serde_json::from_str(r#"[{"elem":8,"name":"O"},["H",1]]"#).unwrap();
+        assert_eq!(list.0["H"].elem, 1);
+
+        // DNEC is a list by default, or a map of arrays, and tombstones are skipped
+        let mut dnec = DNEC::<usize>::new();
+        for (i, name) in ["H", "O", "H", "C", "H"].iter().enumerate() {
+            dnec.push(*name, i);
+        }
+        dnec.tombstone(3);
+        let json = serde_json::to_string(&dnec).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"name":"H","elem":0},{"name":"O","elem":1},{"name":"H","elem":2},{"name":"H","elem":4}]"#
+        );
+        let decoded: DNEC<usize> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.len(), 4);
+        assert_eq!(decoded.get_by_name("H").unwrap(), vec![&0, &2, &4]);
+        assert_eq!(decoded.get_name(1).unwrap(), "O");
+
+        let json = serde_json::to_string(&AsMap(&dnec)).unwrap();
+        assert_eq!(json, r#"{"H":[0,2,4],"O":[1]}"#);
+        let mut buf = Vec::new();
+        as_map::serialize(&dnec, &mut serde_json::Serializer::new(&mut buf)).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), json);
+        let decoded: AsMap<VecDNEC<usize>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.0.len(), 4);
+        assert_eq!(decoded.0.get_by_name("H").unwrap(), vec![&0, &2, &4]);
+
+        // spellings of a normalized name keep their own entries
+        let mut dnec = DNEC::<usize, String, RandomState, _>::with_normalizer(AsciiCaseFold);
+        for (i, name) in ["H", "O", "h", "H"].iter().enumerate() {
+            dnec.push(*name, i);
+        }
+        let json = serde_json::to_string(&AsMap(&dnec)).unwrap();
+        assert_eq!(json, r#"{"H":[0,3],"h":[2],"O":[1]}"#);
+        let decoded: AsMap<DNEC<usize, String, RandomState, AsciiCaseFold>> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.0.get_by_name("h").unwrap(), vec![&0, &3, &2]);
+        assert_eq!(decoded.0.get_name(2).unwrap(), "h");
+
+        // errors are reported by the format
+        assert!(serde_json::from_str::<DNEC<usize>>(r#"[{"name":"H"}]"#).is_err());
+        assert!(serde_json::from_str::<DNEC<usize>>(r#"[{"name":"H","elem":0,"x":1}]"#).is_err());
+    }
+
+    #[test]
+    fn test_text() {
+        use error::{ParseError, ParseErrorKind};
+        use text;
+
+        let input = r#"
+# registry
+  H = hydrogen   # first
+H=deuterium
+"He lium" = "a \"quoted\" # value\n\u{e9}"
+empty =
+O = "  "
+"#;
+        let dnec: DNEC<String> = text::parse(input).unwrap();
+        assert_eq!(dnec.len(), 5);
+        assert_eq!(
+            dnec.get_by_name("H").unwrap(),
+            vec!["hydrogen", "deuterium"]
+        );
+        assert_eq!(
+            *dnec.first_by_name("He lium").unwrap(),
+            "a \"quoted\" # value\n\u{e9}"
+        );
+        assert_eq!(*dnec.first_by_name("empty").unwrap(), "");
+        assert_eq!(*dnec.first_by_name("O").unwrap(), "  ");
+
+        // writing and reading back keeps names, values and order
+        let output = text::to_string(&dnec);
+        assert_eq!(
+            output,
+            "H = hydrogen\nH = deuterium\nHe lium = \"a \\\"quoted\\\" # value\\n\u{e9}\"\nempty = \"\"\nO = \"  \"\n"
+        );
+        let mut tricky = DNEC::<String>::new();
+        tricky.push("a=b", "\t\u{1}\\".to_string());
+        tricky.push("", "#".to_string());
+        tricky.push(" x", "=".to_string());
+        for other in [dnec, tricky] {
+            let back: DNEC<String> = text::parse(&text::to_string(&other)).unwrap();
+            let pairs = |c: &DNEC<String>| -> Vec<_> {
+                c.iter().map(|e| (e.name.clone(), e.elem.clone())).collect()
+            };
+            assert_eq!(pairs(&back), pairs(&other));
+        }
+
+        // errors give their line and column
+        let error = |input: &str| {
+            let result: Result<UNEC<u8>, _> = text::parse(input);
+            result.err().unwrap()
+        };
+        let at = |line, column, kind| ParseError { line, column, kind };
+        assert_eq!(
+            error("H = 1\nHe 2"),
+            at(2, 5, ParseErrorKind::MissingEquals)
+        );
+        assert_eq!(error("H # = 1"), at(1, 3, ParseErrorKind::MissingEquals));
+        assert_eq!(error("  = 1"), at(1, 3, ParseErrorKind::EmptyName));
+        assert_eq!(
+            error("H = \"1"),
+            at(1, 5, ParseErrorKind::UnterminatedQuote)
+        );
+        assert_eq!(
+            error("H = \"\\q\""),
+            at(1, 6, ParseErrorKind::InvalidEscape("\\q".to_string()))
+        );
+        assert_eq!(
+            error("H = \"\\u{110000}\""),
+            at(
+                1,
+                6,
+                ParseErrorKind::InvalidEscape("\\u{110000}".to_string())
+            )
+        );
+        assert_eq!(
+            error("H = \"1\" 2"),
+            at(1, 9, ParseErrorKind::TrailingCharacters)
+        );
+        assert_eq!(error("\n\nH = 1\nH = 2").line, 4);
+        let e = error("H = 300");
+        assert_eq!((e.line, e.column), (1, 5));
+        assert!(matches!(e.kind, ParseErrorKind::InvalidValue(_)));
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 5: invalid value: number too large to fit in target type"
+        );
+    }
 }
@@ -0,0 +1,178 @@
+//! Maps from names to indexes, used as the `hmap` index of a collection. A `HashMap` gives constant time
+//! lookups, while a `BTreeMap` keeps names sorted, which allows iterating in names order and querying names
+//! by prefix or by range.
+use std::borrow::{Borrow, Cow};
+use std::collections::{btree_map, hash_map, BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+
+use adjustable::Adjustable;
+use nec::Indexable;
+
+/// Map from names to the indexes of the elements having these names.
+pub trait NameMap<K, Indexes: Indexable>: Adjustable<K> + Lookup<K, Indexes> {
+    /// Iterator over the names of the map.
+    type Names<'a>: ExactSizeIterator<Item = &'a K>
+    where
+        Self: 'a,
+        K: 'a;
+
+    /// Returns an iterator over the names of the map.
+    fn names(&self) -> Self::Names<'_>;
+
+    /// Shifts by one all indexes from `index`, up for an insertion or down for a removal.
+    fn shift(&mut self, index: usize, inserted: bool);
+
+    /// Lowers each index by the number of `removed` indexes before it, after these elements were removed at
+    /// once. `removed` is sorted.
+    fn shift_removed(&mut self, removed: &[usize]);
+
+    /// Removes all names from the map.
+    fn clear(&mut self);
+}
+
+/// Lookup of a name's indexes, using any borrowed form of the name, like `HashMap::get()` does.
+pub trait Lookup<Q: ?Sized, Indexes: Clone> {
+    /// Returns the indexes of `name`, if any. Maps storing indexes borrow them, while maps computing them
+    /// return them owned.
+    fn get_indexes(&self, name: &Q) -> Option<Cow<'_, Indexes>>;
+
+    /// Removes `name` from the map and returns its indexes, if any.
+    fn remove_indexes(&mut self, name: &Q) -> Option<Indexes>;
+
+    /// Returns the `n`-th index of `name` (starting from 0), if any. Maps computing indexes give it without
+    /// computing the other ones.
+    fn nth_index(&self, name: &Q, n: usize) -> Option<usize>
+    where
+        Indexes: Indexable,
+    {
+        self.get_indexes(name)?.as_slice().get(n).cloned()
+    }
+
+    /// Returns the number of indexes of `name`, 0 if it's not in the map.
+    fn count_indexes(&self, name: &Q) -> usize
+    where
+        Indexes: Indexable,
+    {
+        self.get_indexes(name).map_or(0, |v| v.as_slice().len())
+    }
+}
+
+impl<K, Indexes, S> NameMap<K, Indexes> for HashMap<K, Indexes, S>
+where
+    HashMap<K, Indexes, S>: Adjustable<K>,
+    K: Hash + Eq,
+    Indexes: Indexable,
+    S: BuildHasher,
+{
+    type Names<'a>
+        = hash_map::Keys<'a, K, Indexes>
+    where
+        Self: 'a,
+        K: 'a;
+
+    fn names(&self) -> Self::Names<'_> {
+        self.keys()
+    }
+
+    fn shift(&mut self, index: usize, inserted: bool) {
+        shift(self.values_mut(), index, inserted)
+    }
+
+    fn shift_removed(&mut self, removed: &[usize]) {
+        shift_removed(self.values_mut(), removed)
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self)
+    }
+}
+
+impl<K, Q, Indexes, S> Lookup<Q, Indexes> for HashMap<K, Indexes, S>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    Indexes: Clone,
+    S: BuildHasher,
+{
+    fn get_indexes(&self, name: &Q) -> Option<Cow<'_, Indexes>> {
+        self.get(name).map(Cow::Borrowed)
+    }
+
+    fn remove_indexes(&mut self, name: &Q) -> Option<Indexes> {
+        self.remove(name)
+    }
+}
+
+impl<K, Indexes> NameMap<K, Indexes> for BTreeMap<K, Indexes>
+where
+    BTreeMap<K, Indexes>: Adjustable<K>,
+    K: Ord,
+    Indexes: Indexable,
+{
+    type Names<'a>
+        = btree_map::Keys<'a, K, Indexes>
+    where
+        Self: 'a,
+        K: 'a;
+
+    fn names(&self) -> Self::Names<'_> {
+        self.keys()
+    }
+
+    fn shift(&mut self, index: usize, inserted: bool) {
+        shift(self.values_mut(), index, inserted)
+    }
+
+    fn shift_removed(&mut self, removed: &[usize]) {
+        shift_removed(self.values_mut(), removed)
+    }
+
+    fn clear(&mut self) {
+        BTreeMap::clear(self)
+    }
+}
+
+impl<K, Q, Indexes> Lookup<Q, Indexes> for BTreeMap<K, Indexes>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+    Indexes: Clone,
+{
+    fn get_indexes(&self, name: &Q) -> Option<Cow<'_, Indexes>> {
+        self.get(name).map(Cow::Borrowed)
+    }
+
+    fn remove_indexes(&mut self, name: &Q) -> Option<Indexes> {
+        self.remove(name)
+    }
+}
+
+// shifts by one the indexes from `index`, for maps storing them as values
+fn shift<'a, Indexes, I>(values: I, index: usize, inserted: bool)
+where
+    Indexes: Indexable + 'a,
+    I: Iterator<Item = &'a mut Indexes>,
+{
+    for v in values {
+        for j in v.as_mut_slice() {
+            if inserted && *j >= index {
+                *j += 1;
+            } else if !inserted && *j > index {
+                *j -= 1;
+            }
+        }
+    }
+}
+
+// shifts the indexes by the number of removed ones before them, for maps storing them as values
+fn shift_removed<'a, Indexes, I>(values: I, removed: &[usize])
+where
+    Indexes: Indexable + 'a,
+    I: Iterator<Item = &'a mut Indexes>,
+{
+    for v in values {
+        for j in v.as_mut_slice() {
+            *j -= removed.partition_point(|&k| k < *j);
+        }
+    }
+}
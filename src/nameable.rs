@@ -0,0 +1,57 @@
+//! Elements carrying their own name. If the element type implements the `Nameable` trait, it's
+//! not necessary to provide the element's name when adding it into a collection.
+//!
+//! # Examples
+//! ```
+//! use nec::nec::UNEC;
+//! use nec::nameable::Nameable;
+//!
+//! struct Atom { symbol: String, proton: u8, };
+//! impl Nameable for Atom {
+//!     fn name(&self) -> &str {
+//!         &self.symbol
+//!     }
+//! }
+//!
+//! let mut molecule = UNEC::<Atom>::new();
+//! molecule.push_named(Atom { symbol: "H".to_string(), proton: 1 });
+//! molecule.push_named(Atom { symbol: "He".to_string(), proton: 2 });
+//!
+//! assert_eq!(molecule["He"].elem.proton, 2);
+//! ```
+
+/// Trait to implement for elements which hold their own name.
+pub trait Nameable {
+    /// Returns the element's name.
+    fn name(&self) -> &str;
+}
+
+/// A `(name, element)` tuple is named by its first field.
+///
+/// # Examples
+///
+/// ```
+/// use nec::nameable::Nameable;
+///
+/// let t = ("Hydrogen".to_string(), 1);
+/// assert_eq!(t.name(), "Hydrogen");
+/// ```
+impl<Element> Nameable for (String, Element) {
+    fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+/// # Examples
+///
+/// ```
+/// use nec::nameable::Nameable;
+///
+/// let t = ("Hydrogen", 1);
+/// assert_eq!(t.name(), "Hydrogen");
+/// ```
+impl<Element> Nameable for (&str, Element) {
+    fn name(&self) -> &str {
+        self.0
+    }
+}
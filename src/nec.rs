@@ -2,7 +2,8 @@
 //! It mimics the `Vector` collection, but with the ability to retrieve an element by its name.
 //!
 //! Element names could also be duplicated, and getting an element providing its name could possibly return several elements.
-//! The name should be provided when pushing an element into the collection.
+//! If the element type implements the `Nameable` trait, it's not necessary to provide the element's name. If not,
+//! the name should be provided when pushing an element into the collection.
 //!
 //! # Examples
 //! ```
@@ -29,58 +30,467 @@
 //!
 //! assert_eq!(water.len(), 2);
 //! ```
+//!
+//! Names are `String` by default, but any type implementing `Hash`, `Eq` and `Clone` could be used instead:
+//! ```
+//! use nec::nec::UNEC;
+//!
+//! struct Atom { symbol: &'static str, neutron: u8, };
+//! let mut table = UNEC::<Atom, u8>::new();
+//!
+//! table.push(1, Atom{ symbol:"H", neutron:0 });
+//! table.push(8, Atom{ symbol:"O", neutron:8 });
+//!
+//! assert_eq!(table[&8].elem.symbol, "O");
+//! ```
 
-use std::collections::HashMap;
+use std::borrow::{Borrow, Cow};
+use std::cmp::Ordering;
+use std::collections::hash_map::RandomState;
+use std::collections::{btree_map, BTreeMap, HashMap};
 use std::convert::From;
 use std::fmt;
-use std::ops::{Index, IndexMut};
+use std::hash::{BuildHasher, Hash};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::ops::{Bound, Deref, DerefMut, Index, IndexMut, RangeBounds};
 use std::slice::{Iter, IterMut};
+use std::sync::Arc;
 
-use adjustable::Adjustable;
+use alias::AliasFormat;
+use entry::Entry;
+use error::NecError;
+use glob;
+use handle::{Handle, Handles, TOMBSTONE};
+use map::{Lookup, NameMap};
+use nameable::Nameable;
+use normalizer::{Normalizer, Verbatim};
+use offset::OffsetMap;
+use policy::DuplicatePolicy;
+use secondary::{KeyIndex, SecondaryIndex, SecondaryIndexes};
 
 /// Encompassing structure for storing not only the element object, by also its connected data: its original name
-/// and a possible unique name which can be defined in case of duplicated elements. This unique name
-/// could be then used to refer to a particular element in the collection.
+/// and a possible unique name which can be defined in case of duplicated elements. This unique name, or alias,
+/// is given to DNEC elements once an alias format is set, and could be then used to refer to a particular
+/// element in the collection.
 #[derive(Debug, Clone)]
-pub struct ElementBundle<Element> {
+pub struct ElementBundle<Element, K = String> {
     // keep element inside this struct
     pub elem: Element,
-    // keep its original name and its alternate unique name if any
-    pub name: String,
+    // keep its original name
+    pub name: K,
+    // and its alternate unique name if any
+    pub alias: Option<K>,
+    // and its extra aliases
+    pub aliases: Vec<K>,
 }
 
-/// Just a trick to restrict the list of admissible type for `Indexes` type parameter below.
-pub trait Indexable {}
-impl Indexable for usize {}
-impl Indexable for Vec<usize> {}
+/// Just a trick to restrict the list of admissible type for `Indexes` type parameter below. It also gives
+/// access to the indexes as a slice, whatever their number.
+pub trait Indexable: Clone {
+    fn as_slice(&self) -> &[usize];
+    fn as_mut_slice(&mut self) -> &mut [usize];
+}
+impl Indexable for usize {
+    fn as_slice(&self) -> &[usize] {
+        ::std::slice::from_ref(self)
+    }
+    fn as_mut_slice(&mut self) -> &mut [usize] {
+        ::std::slice::from_mut(self)
+    }
+}
+impl Indexable for Vec<usize> {
+    fn as_slice(&self) -> &[usize] {
+        self
+    }
+    fn as_mut_slice(&mut self) -> &mut [usize] {
+        self
+    }
+}
 
 /// Named elements collection. The `Indexes` type parameter could either by a simple `usize` index in case of non-duplicated elements,
 /// or a `Vec<usize>` for storing elements having the same name. The `Indexable` trait bound is used to restrict the set
-/// of admissible types: `usize` or `Vec<usize>`.
+/// of admissible types: `usize` or `Vec<usize>`. The `K` type parameter is the type of elements' names, which
+/// is `String` by default. The `M` type parameter is the map from names to indexes, which is a `HashMap` by
+/// default, or a `BTreeMap` to keep names sorted. The `N` type parameter is the normalizer applied to names
+/// stored into and looked up in the map, which keeps names verbatim by default.
 //#[derive(Clone)]
-pub struct NamedElementsCollection<Element, Indexes: Indexable> {
+pub struct NamedElementsCollection<
+    Element,
+    Indexes: Indexable,
+    K = String,
+    M = HashMap<K, Indexes>,
+    N = Verbatim,
+> {
     /// List of Element structs
-    pub list: Vec<ElementBundle<Element>>,
-    /// Hashmap keeping track of the name vs. index (or indexes) of the structure in the previous list
-    pub hmap: HashMap<String, Indexes>,
+    pub list: Vec<ElementBundle<Element, K>>,
+    /// Map keeping track of the name vs. index (or indexes) of the structure in the previous list
+    pub hmap: M,
+    // what to do when adding a name already in a UNEC
+    policy: DuplicatePolicy<Element>,
+    // applied to names stored into and looked up in hmap
+    normalizer: N,
+    // Indexes is only used through M
+    indexes: PhantomData<Indexes>,
+    // elements' aliases, once an alias format is set or an alias is added
+    aliases: Option<Aliases<K, M>>,
+    // indexes of the elements by keys extracted from them
+    secondary: SecondaryIndexes<Element>,
+    // handles of the elements, following their moves
+    handles: Handles,
+}
+
+// map keeping track of the alias vs. index of the elements, and how to build new aliases
+struct Aliases<K, M> {
+    format: Option<AliasFormat<K>>,
+    map: M,
+}
+
+impl<K, M> Aliases<K, M> {
+    // shifts by one the indexes from `index`, up for an insertion or down for a removal
+    fn shift<Indexes>(&mut self, index: usize, inserted: bool)
+    where
+        M: NameMap<K, Indexes>,
+        Indexes: Indexable,
+    {
+        self.map.shift(index, inserted)
+    }
+}
+
+impl<K, M: Clone> Clone for Aliases<K, M> {
+    fn clone(&self) -> Self {
+        Aliases {
+            format: self.format,
+            map: self.map.clone(),
+        }
+    }
+}
+
+// applies `f` on the aliases map with each normalized alias of `bundle`, if the collection has aliases
+fn adjust_aliases<Element, K, M, N, F>(
+    aliases: &mut Option<Aliases<K, M>>,
+    normalizer: &N,
+    bundle: &ElementBundle<Element, K>,
+    mut f: F,
+) where
+    K: Clone,
+    N: Normalizer<K>,
+    F: FnMut(&mut M, &K),
+{
+    if let Some(aliases) = aliases.as_mut() {
+        for alias in bundle.alias.iter().chain(&bundle.aliases) {
+            f(&mut aliases.map, &normalizer.normalize(alias));
+        }
+    }
+}
+
+// panic message when the Error duplicate policy is used by a method which doesn't return a Result
+const DUPLICATE_PANIC: &str = "name is already in the collection and duplicate policy is Error";
+
+// where a bundle added with others at the end of the collection goes
+enum Destination<K> {
+    // onto the element of a UNEC at this index, following the duplicate policy
+    Existing(usize),
+    // onto the n-th bundle added at the end, following the duplicate policy
+    Added(usize),
+    // at the end of the collection, with this alias and these extra aliases
+    New(Option<K>, Vec<K>),
+}
+
+impl<Element, Indexes, K, S, N>
+    NamedElementsCollection<Element, Indexes, K, HashMap<K, Indexes, S>, N>
+where
+    HashMap<K, Indexes, S>: NameMap<K, Indexes>,
+    K: Hash + Eq + Clone,
+    S: BuildHasher,
+    N: Normalizer<K>,
+    Indexes: Indexable,
+{
+    /// Creates a new empty collection of named elements, using `hash_builder` to hash names, like
+    /// `HashMap::with_hasher()` does.
+    ///
+    /// # Arguments
+    /// * `hash_builder` - Hasher builder used for the names hashmap
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::BuildHasherDefault;
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom, String, BuildHasherDefault<DefaultHasher>>::with_hasher(
+    ///     BuildHasherDefault::default(),
+    /// );
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// assert_eq!(molecule["Hydrogen"].elem.proton, 1);
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Self
+    where
+        N: Default,
+    {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    /// Creates a new empty collection of named elements with room for at least `capacity` elements
+    /// without reallocating, using `hash_builder` to hash names.
+    ///
+    /// # Arguments
+    /// * `capacity` - Number of elements the collection can hold without reallocating
+    /// * `hash_builder` - Hasher builder used for the names hashmap
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let molecule = UNEC::<Atom>::with_capacity_and_hasher(10, RandomState::new());
+    ///
+    /// assert!(molecule.list.capacity() >= 10);
+    /// assert!(molecule.hmap.capacity() >= 10);
+    /// ```
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self
+    where
+        N: Default,
+    {
+        NamedElementsCollection::from_parts(
+            capacity,
+            HashMap::with_capacity_and_hasher(capacity, hash_builder),
+            N::default(),
+        )
+    }
+
+    /// Returns a reference to the collection's hasher builder.
+    pub fn hasher(&self) -> &S {
+        self.hmap.hasher()
+    }
+}
+
+impl<Element, K, S, N> NamedElementsCollection<Element, Vec<usize>, K, OffsetMap<K, S>, N>
+where
+    K: Hash + Eq + Clone,
+    S: BuildHasher,
+    N: Normalizer<K>,
+{
+    /// Creates a new empty collection of named elements, using `hash_builder` to hash names, like
+    /// `HashMap::with_hasher()` does.
+    ///
+    /// # Arguments
+    /// * `hash_builder` - Hasher builder used for the names
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::BuildHasherDefault;
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom, String, BuildHasherDefault<DefaultHasher>>::with_hasher(
+    ///     BuildHasherDefault::default(),
+    /// );
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    /// assert_eq!(water.get_by_name("Hydrogen").unwrap().len(), 2);
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Self
+    where
+        N: Default,
+    {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    /// Creates a new empty collection of named elements with room for at least `capacity` elements
+    /// without reallocating, using `hash_builder` to hash names.
+    ///
+    /// # Arguments
+    /// * `capacity` - Number of elements the collection can hold without reallocating
+    /// * `hash_builder` - Hasher builder used for the names
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let molecule = DNEC::<Atom>::with_capacity_and_hasher(10, RandomState::new());
+    ///
+    /// assert!(molecule.list.capacity() >= 10);
+    /// assert!(molecule.hmap.capacity() >= 10);
+    /// ```
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self
+    where
+        N: Default,
+    {
+        NamedElementsCollection::from_parts(
+            capacity,
+            OffsetMap::with_capacity_and_hasher(capacity, hash_builder),
+            N::default(),
+        )
+    }
+
+    /// Returns a reference to the collection's hasher builder.
+    pub fn hasher(&self) -> &S {
+        self.hmap.hasher()
+    }
 }
 
-impl<Element, Indexes> NamedElementsCollection<Element, Indexes>
+impl<Element, Indexes, K, M, N> NamedElementsCollection<Element, Indexes, K, M, N>
 where
-    HashMap<String, Indexes>: Adjustable,
+    M: NameMap<K, Indexes>,
+    K: Eq + Clone,
+    N: Normalizer<K>,
     Indexes: Indexable,
 {
     /// Creates a new empty collection of named elements.
-    pub fn new() -> NamedElementsCollection<Element, Indexes> {
+    pub fn new() -> Self
+    where
+        M: Default,
+        N: Default,
+    {
+        NamedElementsCollection::from_parts(0, M::default(), N::default())
+    }
+
+    /// Creates a new empty collection of named elements, using `policy` when adding a name already in
+    /// the collection.
+    ///
+    /// # Arguments
+    /// * `policy` - Duplicate policy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    /// use nec::policy::DuplicatePolicy;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::with_duplicate_policy(DuplicatePolicy::Error);
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// assert!(molecule.try_push("Hydrogen", Atom{ proton:1, neutron:0 }).is_err());
+    /// ```
+    pub fn with_duplicate_policy(policy: DuplicatePolicy<Element>) -> Self
+    where
+        M: Default,
+        N: Default,
+    {
+        let mut collection = NamedElementsCollection::new();
+        collection.policy = policy;
+        collection
+    }
+
+    /// Creates a new empty collection of named elements, using `normalizer` on names stored into and looked up
+    /// in the map. Elements keep the spelling of their names as it was first given.
+    ///
+    /// # Arguments
+    /// * `normalizer` - Names normalizer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    /// use nec::nec::UNEC;
+    /// use nec::normalizer::{AsciiCaseFold, FoldSeparators};
+    ///
+    /// let mut config = UNEC::<i32, String, RandomState, _>::with_normalizer((AsciiCaseFold, FoldSeparators));
+    ///
+    /// config.push("Max_Connections", 10);
+    /// config.push("max-connections", 20);
+    ///
+    /// assert_eq!(config.len(), 1);
+    /// assert_eq!(config["MAX-CONNECTIONS"].elem, 20);
+    /// assert_eq!(config.get_name(0).unwrap(), "Max_Connections");
+    /// ```
+    pub fn with_normalizer(normalizer: N) -> Self
+    where
+        M: Default,
+    {
+        NamedElementsCollection::from_parts(0, M::default(), normalizer)
+    }
+
+    fn from_parts(capacity: usize, hmap: M, normalizer: N) -> Self {
         NamedElementsCollection {
             // list is a vector of elements
-            list: Vec::new(),
+            list: Vec::with_capacity(capacity),
+
+            // hmap is a map of indexes of the list elements
+            hmap,
+
+            policy: DuplicatePolicy::default(),
+
+            normalizer,
 
-            // hmap is a hashmap of indexes of the list elements
-            hmap: HashMap::new(),
+            indexes: PhantomData,
+
+            aliases: None,
+
+            secondary: SecondaryIndexes::new(),
+            handles: Handles::default(),
         }
     }
 
+    /// Returns a reference to the collection's names normalizer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    /// use nec::nec::UNEC;
+    /// use nec::normalizer::{Normalizer, Trim};
+    ///
+    /// let molecule = UNEC::<u8, String, RandomState, _>::with_normalizer(Trim);
+    /// assert_eq!(molecule.normalizer().normalize(" Hydrogen "), "Hydrogen");
+    /// ```
+    pub fn normalizer(&self) -> &N {
+        &self.normalizer
+    }
+
+    /// Returns the policy used when adding a name already in the collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    /// use nec::policy::DuplicatePolicy;
+    ///
+    /// let molecule = UNEC::<()>::new();
+    /// assert!(matches!(molecule.duplicate_policy(), DuplicatePolicy::KeepLast));
+    /// ```
+    pub fn duplicate_policy(&self) -> &DuplicatePolicy<Element> {
+        &self.policy
+    }
+
+    /// Sets the policy used when adding a name already in the collection.
+    ///
+    /// # Arguments
+    /// * `policy` - Duplicate policy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    /// use nec::policy::DuplicatePolicy;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// let bonus = 1;
+    /// let policy = DuplicatePolicy::merge(move |a: &mut Atom, b: Atom| a.neutron += b.neutron + bonus);
+    /// molecule.set_duplicate_policy(policy);
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    /// assert_eq!(molecule["Hydrogen"].elem.neutron, 2);
+    /// ```
+    pub fn set_duplicate_policy(&mut self, policy: DuplicatePolicy<Element>) {
+        self.policy = policy;
+    }
+
     /// Tests whether the collection contains an item by providing its name.
     ///
     /// # Arguments
@@ -98,8 +508,13 @@ where
     /// assert!(molecule.contains_name("Hydrogen"));
     /// assert!(!molecule.contains_name("Helium"));
     /// ```
-    pub fn contains_name(&self, name: &str) -> bool {
-        self.hmap.contains_key(name)
+    pub fn contains_name<Q>(&self, name: &Q) -> bool
+    where
+        M: Lookup<Q, Indexes>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        self.lookup_nth(name, 0, false).is_some()
     }
 
     /// Returns the reference on the element corresponding to `index`.
@@ -123,7 +538,10 @@ where
     /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
     /// assert_eq!(molecule.get(0).unwrap().elem.proton,1);
     /// ```
-    pub fn get(&self, index: usize) -> Option<&ElementBundle<Element>> {
+    pub fn get(&self, index: usize) -> Option<&ElementBundle<Element, K>> {
+        if self.handles.is_dead(index) {
+            return None;
+        }
         self.list.get(index)
     }
 
@@ -148,11 +566,18 @@ where
     /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
     /// assert_eq!(molecule.get(0).unwrap().elem.proton,1);
     /// ```
-    pub fn get_mut(&mut self, index: usize) -> Option<&mut ElementBundle<Element>> {
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut ElementBundle<Element, K>> {
+        if self.handles.is_dead(index) {
+            return None;
+        }
+        self.secondary.touch();
         self.list.get_mut(index)
     }
 
-    /// Returns the number of elements in the collection.
+    /// Returns the number of elements in the collection, tombstones included: indexes given to and returned
+    /// by the methods of the collection are positions in `list`, from 0 to `len() - 1`, and a tombstone keeps
+    /// its position until `compact()` is called. Use `live_len()` to count the elements not removed, and
+    /// `iter_indexed()` to get the positions of the elements while iterating.
     ///
     /// # Examples
     ///
@@ -165,12 +590,37 @@ where
     /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
     /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
     /// assert_eq!(molecule.len(),2);
+    ///
+    /// molecule.tombstone(0);
+    /// assert_eq!(molecule.len(),2);
+    /// assert_eq!(molecule.live_len(),1);
     /// ```
     pub fn len(&self) -> usize {
         self.list.len()
     }
 
-    /// Tests whether the collection contains elements.
+    /// Returns the number of elements in the collection, tombstones excluded. It's also the number of elements
+    /// given by `iter()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.tombstone(1);
+    /// assert_eq!(water.live_len(), 1);
+    /// assert_eq!(water.iter().count(), 1);
+    /// ```
+    pub fn live_len(&self) -> usize {
+        self.list.len() - self.handles.dead()
+    }
+
+    /// Tests whether the collection contains elements, tombstones included, like `len()` counts them.
     ///
     /// # Examples
     ///
@@ -184,10 +634,11 @@ where
     /// assert!(!molecule.is_empty());
     /// ```
     pub fn is_empty(&self) -> bool {
-        self.list.is_empty()
+        self.len() == 0
     }
 
-    /// Returns an iterator over the collection.
+    /// Returns an iterator over the collection. Tombstones are skipped, so once an element is tombstoned,
+    /// counting the elements with `enumerate()` doesn't give their indexes anymore: use `iter_indexed()` instead.
     ///
     /// # Examples
     ///
@@ -207,10 +658,37 @@ where
     /// assert_eq!(iter.next().unwrap().elem.proton, 1);
     /// assert_eq!(iter.next().unwrap().elem.proton, 8);
     /// ```
-    pub fn iter(&self) -> NecIter<Element> {
+    pub fn iter(&self) -> NecIter<'_, Element, K> {
         self.into_iter()
     }
 
+    /// Returns an iterator over the collection, as `(index, bundle)` pairs. Tombstones are skipped, while
+    /// indexes are still positions in `list`: they can be used with `get()` or indexing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.tombstone(0);
+    ///
+    /// let indexes: Vec<_> = water.iter_indexed().map(|(i, _)| i).collect();
+    /// assert_eq!(indexes, vec![1, 2]);
+    /// assert_eq!(water[2].name, "Hydrogen");
+    /// ```
+    pub fn iter_indexed(&self) -> NecIterIndexed<'_, Element, K> {
+        NecIterIndexed {
+            iter: self.list.iter().enumerate(),
+            handles: &self.handles,
+        }
+    }
+
     /// Returns a mutable iterator over the collection.
     ///
     /// # Examples
@@ -232,7 +710,7 @@ where
     /// oxygen.elem.proton = 8;
     /// oxygen.elem.neutron = 8;
     /// ```
-    pub fn iter_mut(&mut self) -> NecIterMut<Element> {
+    pub fn iter_mut(&mut self) -> NecIterMut<'_, Element, K> {
         self.into_iter()
     }
 
@@ -255,29 +733,80 @@ where
     pub fn clear(&mut self) {
         self.list.clear();
         self.hmap.clear();
+        if let Some(ref mut aliases) = self.aliases {
+            aliases.map.clear();
+        }
+        self.rebuild_indexes();
+        self.handles.resize(0);
     }
 
-    // pub fn insert(&mut self, index: usize, name: &str, element: Element) {
-    //     // insert element at index in the vector
-    //     self.list.insert(
-    //         index,
-    //         ElementBundle {
-    //             elem: element,
-    //             name: String::from(name),
-    //         },
-    //     );
-
-    //     // manage indexes in the hash
-    //     let name = self.list.get(index).unwrap().name.clone();
-    //     self.hmap.insert_element(&name, index);
-
-    //     e
-    // }
+    /// Inserts an element at position `index`, shifting all elements after it to the right.
+    ///
+    /// For a UNEC, if `name` is already in the collection, the collection's duplicate policy is applied
+    /// like `push()` does and `index` is not used: with the default policy, the element is replaced in place and
+    /// the replaced element is returned. Otherwise `None` is returned. Use `insert_handle()` to get the handle of
+    /// the element instead.
+    ///
+    /// # Arguments
+    /// * `index` - Position of the inserted element
+    /// * `name` - Element's name
+    /// * `element` - Element structure
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`, or if the duplicate policy is `Error` and `name` is already in the collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Lithium", Atom{ proton:3, neutron:4 });
+    ///
+    /// assert!(molecule.insert(1, "Helium", Atom{ proton:2, neutron:2 }).is_none());
+    /// assert_eq!(molecule.get_name(1).unwrap(), "Helium");
+    /// assert_eq!(molecule["Lithium"].elem.proton, 3);
+    ///
+    /// // Helium is already there: it's replaced in place
+    /// let old = molecule.insert(0, "Helium", Atom{ proton:2, neutron:1 }).unwrap();
+    /// assert_eq!(old.elem.neutron, 2);
+    /// assert_eq!(molecule.get_name(1).unwrap(), "Helium");
+    /// assert_eq!(molecule["Helium"].elem.neutron, 1);
+    /// ```
+    pub fn insert<T: Into<K>>(
+        &mut self,
+        index: usize,
+        name: T,
+        element: Element,
+    ) -> Option<ElementBundle<Element, K>> {
+        self.insert_bundle(
+            index,
+            ElementBundle {
+                elem: element,
+                name: name.into(),
+                alias: None,
+                aliases: Vec::new(),
+            },
+        )
+        .1
+    }
 
-    /// Returns the list of elements' names, without duplication.
+    /// Inserts an element at position `index` like `insert()`, and returns the handle of the element now
+    /// named `name`. For a UNEC, if `name` is already in the collection, the duplicate policy is applied and the
+    /// element displaced, if any, is dropped, like `push_handle()` does.
     ///
     /// # Arguments
+    /// * `index` - Position of the inserted element
     /// * `name` - Element's name
+    /// * `element` - Element structure
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`, or if the duplicate policy is `Error` and `name` is already in the collection.
     ///
     /// # Examples
     ///
@@ -288,198 +817,3120 @@ where
     /// let mut water = DNEC::<Atom>::new();
     ///
     /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
-    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
-    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
-    /// assert_eq!(water.names().len(), 2);
+    /// let o = water.insert_handle(0, "Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.insert(0, "Hydrogen", Atom{ proton:1, neutron:1 });
+    /// assert_eq!(water.index_of_handle(o), Some(1));
     /// ```
-    pub fn names(&self) -> Vec<String> {
-        self.hmap.keys().map(|e| e.clone()).collect()
+    pub fn insert_handle<T: Into<K>>(&mut self, index: usize, name: T, element: Element) -> Handle {
+        let (index, _) = self.insert_bundle(
+            index,
+            ElementBundle {
+                elem: element,
+                name: name.into(),
+                alias: None,
+                aliases: Vec::new(),
+            },
+        );
+        self.handles.get(index).unwrap()
     }
 
-    /// Returns the name of the element at `index`.
+    // inserts an already built bundle, following the insert() semantics, and returns the index of the element
+    // now having its name, with the element displaced if any
+    fn insert_bundle(
+        &mut self,
+        index: usize,
+        mut bundle: ElementBundle<Element, K>,
+    ) -> (usize, Option<ElementBundle<Element, K>>) {
+        assert!(
+            index <= self.list.len(),
+            "insertion index (is {}) should be <= len (is {})",
+            index,
+            self.list.len()
+        );
+        let found = self.already_in(&self.normalizer.normalize(&bundle.name));
+        match found {
+            // if name is already in our list, apply policy
+            Some(i) => {
+                let policy = self.policy.clone();
+                let displaced = self
+                    .resolve_duplicate(i, bundle, &policy)
+                    .unwrap_or_else(|_| panic!("{}", DUPLICATE_PANIC));
+                (i, displaced)
+            }
+            // if not, insert element and shift indexes in the hash
+            None => {
+                bundle.alias = self.new_alias(&bundle.name);
+                self.hmap
+                    .insert_element(&self.normalizer.normalize(&bundle.name), index);
+                if let Some(ref mut aliases) = self.aliases {
+                    aliases.shift(index, true);
+                }
+                self.refresh_indexes();
+                self.secondary.shift(index, true);
+                self.secondary.add(&bundle.elem, index);
+                self.handles.insert(index);
+                adjust_aliases(&mut self.aliases, &self.normalizer, &bundle, |m, a| {
+                    m.add_element(a, index)
+                });
+                self.list.insert(index, bundle);
+                (index, None)
+            }
+        }
+    }
+
+    /// Gets the entry for `name` in the collection, for in-place manipulation.
     ///
     /// # Arguments
-    /// * `index` - Element index.
+    /// * `name` - Element's name
     ///
-    /// # Panics
+    /// # Examples
     ///
-    /// Panics if `index` is out of bounds.
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    ///
+    /// water.entry("Hydrogen").or_insert(Atom{ proton:1, neutron:0 }).neutron = 1;
+    /// water.entry("Oxygen").or_insert(Atom{ proton:8, neutron:8 });
+    /// assert_eq!(water.len(), 2);
+    /// assert_eq!(water[0].elem.neutron, 1);
+    /// ```
+    pub fn entry<T: Into<K>>(&mut self, name: T) -> Entry<'_, Element, Indexes, K, M, N> {
+        Entry::new(self, name.into())
+    }
+
+    /// Returns the list of elements' names, without duplication. Names are returned in their normalized form.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// assert_eq!(water.names().len(), 2);
+    /// ```
+    pub fn names(&self) -> Vec<K> {
+        self.iter_names().cloned().collect()
+    }
+
+    /// Returns an iterator over the elements' names, without duplication and in no particular order. Names are
+    /// yielded in their normalized form.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    ///
+    /// let mut names: Vec<&String> = water.iter_names().collect();
+    /// names.sort();
+    /// assert_eq!(names, vec!["Hydrogen", "Oxygen"]);
+    /// ```
+    pub fn iter_names(&self) -> NecNames<'_, Indexes, K, M> {
+        NecNames {
+            iter: self.hmap.names(),
+        }
+    }
+
+    /// Returns an iterator over the elements named `name`, in the collection order. If `name` is not
+    /// found, the iterator is empty.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// let neutrons: Vec<_> = water.iter_by_name("Hydrogen").map(|h| h.neutron).collect();
+    /// assert_eq!(neutrons, vec![0, 1]);
+    /// assert_eq!(water.iter_by_name("Helium").count(), 0);
+    /// ```
+    pub fn iter_by_name<Q>(&self, name: &Q) -> NecByName<'_, Element, K>
+    where
+        M: Lookup<Q, Indexes>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        NecByName {
+            iter: self.iter_bundles_by_name(name),
+        }
+    }
+
+    /// Returns an iterator over the element bundles named `name`, in the collection order. If `name` is not
+    /// found, the iterator is empty.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    ///
+    /// let mut iter = molecule.iter_bundles_by_name("Helium");
+    /// assert_eq!(iter.next().unwrap().elem.proton, 2);
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn iter_bundles_by_name<Q>(&self, name: &Q) -> NecBundlesByName<'_, Element, K>
+    where
+        M: Lookup<Q, Indexes>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let indexes = match self.lookup(name) {
+            Some(Cow::Borrowed(v)) => Cow::Borrowed(v.as_slice()),
+            Some(Cow::Owned(v)) => Cow::Owned(v.as_slice().to_vec()),
+            None => Cow::Borrowed(&[][..]),
+        };
+
+        NecBundlesByName {
+            list: &self.list,
+            positions: 0..indexes.len(),
+            indexes,
+        }
+    }
+
+    /// Returns the name of the element at `index`.
+    ///
+    /// # Arguments
+    /// * `index` - Element index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    /// assert_eq!(molecule.get_name(1).unwrap(), "Helium");
+    /// ```
+    pub fn get_name(&self, index: usize) -> Option<&K> {
+        match self.get(index) {
+            Some(v) => Some(&v.name),
+            None => None,
+        }
+    }
+
+    /// Renames the element at `index`. Its position in the collection is not changed.
+    ///
+    /// For a UNEC, if `new_name` is already used by another element, the element is not renamed and
+    /// `NecError::DuplicateName` is returned.
+    ///
+    /// # Arguments
+    /// * `index` - Element index.
+    /// * `new_name` - Element's new name
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds or is a tombstone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Hellium", Atom{ proton:2, neutron:2 });
+    ///
+    /// assert!(molecule.rename(1, "Helium").is_ok());
+    /// assert_eq!(molecule.get_name(1).unwrap(), "Helium");
+    /// assert_eq!(molecule["Helium"].elem.proton, 2);
+    /// assert!(!molecule.contains_name("Hellium"));
+    ///
+    /// // Hydrogen is already taken
+    /// assert!(molecule.rename(1, "Hydrogen").is_err());
+    /// ```
+    pub fn rename<T: Into<K>>(&mut self, index: usize, new_name: T) -> Result<(), NecError<K>> {
+        self.assert_live(index);
+        let new_name = new_name.into();
+        let new_key = self.normalizer.normalize(&new_name);
+
+        // same name, only its spelling could change
+        if *self.normalizer.normalize(&self.list[index].name) == *new_key {
+            self.list[index].name = new_name;
+            return Ok(());
+        }
+
+        if self.already_in(&new_key).is_some() {
+            return Err(NecError::DuplicateName(new_name));
+        }
+
+        let bundle = &mut self.list[index];
+        self.hmap
+            .remove_index(&self.normalizer.normalize(&bundle.name), index);
+        self.hmap.add_element(&new_key, index);
+        bundle.name = new_name;
+
+        Ok(())
+    }
+
+    /// Renames all elements named `old_name`, without changing their positions in the collection.
+    ///
+    /// If `old_name` is not found, `NecError::NameNotFound` is returned. For a UNEC, if `new_name` is
+    /// already used by another element, nothing is renamed and `NecError::DuplicateName` is returned.
+    ///
+    /// # Arguments
+    /// * `old_name` - Elements' current name
+    /// * `new_name` - Elements' new name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogne", Atom{ proton:1, neutron:0 });
+    /// water.push("Hydrogne", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    ///
+    /// assert!(water.rename_by_name("Hydrogne", "Hydrogen").is_ok());
+    /// assert_eq!(water.get_by_name("Hydrogen").unwrap().len(), 2);
+    /// assert!(!water.contains_name("Hydrogne"));
+    /// assert!(water.rename_by_name("Hydrogne", "Hydrogen").is_err());
+    /// ```
+    pub fn rename_by_name<O, T>(&mut self, old_name: O, new_name: T) -> Result<(), NecError<K>>
+    where
+        O: Into<K>,
+        T: Into<K>,
+    {
+        let (old_name, new_name) = (old_name.into(), new_name.into());
+        let (old_key, new_key) = (
+            self.normalizer.normalize(&old_name),
+            self.normalizer.normalize(&new_name),
+        );
+        let indexes = match self.hmap.get_indexes(&*old_key) {
+            Some(v) => v.as_slice().to_vec(),
+            None => return Err(NecError::NameNotFound(old_name)),
+        };
+
+        // same name, only its spelling could change
+        if old_key == new_key {
+            for index in indexes {
+                self.list[index].name = new_name.clone();
+            }
+            return Ok(());
+        }
+
+        if self.already_in(&new_key).is_some() {
+            return Err(NecError::DuplicateName(new_name));
+        }
+
+        for index in indexes {
+            self.hmap.remove_index(&old_key, index);
+            self.hmap.add_element(&new_key, index);
+            self.list[index].name = new_name.clone();
+        }
+
+        Ok(())
+    }
+
+    /// Returns the index of the element aliased `alias`. If `alias` is not found, `None` is returned.
+    ///
+    /// # Arguments
+    /// * `alias` - Element's alias
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::alias::hash_suffix;
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::with_alias_format(hash_suffix);
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// assert_eq!(water.index_of_alias("Hydrogen#1"), Some(2));
+    /// water.swap(1, 2);
+    /// assert_eq!(water.index_of_alias("Hydrogen#1"), Some(1));
+    /// assert!(water.index_of_alias("Hydrogen#2").is_none());
+    /// ```
+    pub fn index_of_alias<Q>(&self, alias: &Q) -> Option<usize>
+    where
+        M: Lookup<Q, Indexes>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        self.aliases
+            .as_ref()?
+            .map
+            .get_indexes(&*self.normalizer.normalize(alias))?
+            .as_slice()
+            .first()
+            .cloned()
+    }
+
+    /// Returns the format of the aliases given to new elements, if any.
+    pub fn alias_format(&self) -> Option<AliasFormat<K>> {
+        self.aliases.as_ref()?.format
+    }
+
+    // returns the collection's aliases, creating them if needed
+    fn aliases_mut(&mut self) -> &mut Aliases<K, M>
+    where
+        M: Default,
+    {
+        self.aliases.get_or_insert_with(|| Aliases {
+            format: None,
+            map: M::default(),
+        })
+    }
+
+    /// Adds an extra alias to the element at `index`. Aliases resolve to their element in name lookups,
+    /// unless an element has the same name, and follow it when it is moved. They are dropped with it
+    /// when it is removed.
+    ///
+    /// If `alias` is already used as an alias, nothing is changed and `NecError::DuplicateName` is returned.
+    /// For a UNEC, this is also the case if `alias` is already used as a name. Conversely, adding a UNEC
+    /// element named like an alias is a duplicate, and renaming an element like an alias is an error.
+    ///
+    /// # Arguments
+    /// * `index` - Element index
+    /// * `alias` - Element's new alias
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds or is a tombstone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    ///
+    /// assert!(molecule.add_alias(0, "H").is_ok());
+    /// assert!(molecule.add_alias(0, "Protium").is_ok());
+    /// assert_eq!(molecule["H"].elem.proton, 1);
+    /// assert_eq!(molecule.get_by_name("Protium").unwrap().proton, 1);
+    ///
+    /// // already used as an alias or as a name
+    /// assert!(molecule.add_alias(1, "H").is_err());
+    /// assert!(molecule.add_alias(1, "Hydrogen").is_err());
+    ///
+    /// // aliases follow their element
+    /// molecule.swap(0, 1);
+    /// assert_eq!(molecule.index_of_alias("Protium"), Some(1));
+    /// molecule.remove(1);
+    /// assert!(!molecule.contains_name("H"));
+    /// ```
+    pub fn add_alias<T: Into<K>>(&mut self, index: usize, alias: T) -> Result<(), NecError<K>>
+    where
+        M: Default,
+    {
+        assert!(
+            index < self.list.len(),
+            "index (is {}) should be < len (is {})",
+            index,
+            self.list.len()
+        );
+        self.assert_live(index);
+
+        let alias = alias.into();
+        let key = self.normalizer.normalize(&alias);
+        let used = self.hmap.already_in(&key).is_some()
+            || self
+                .aliases
+                .as_ref()
+                .is_some_and(|aliases| aliases.map.get_indexes(&key).is_some());
+        if used {
+            return Err(NecError::DuplicateName(alias));
+        }
+
+        self.aliases_mut().map.add_element(&key, index);
+        self.list[index].aliases.push(alias);
+        Ok(())
+    }
+
+    /// Removes `alias` from the element having it, and returns this element's index. The alias could
+    /// be an extra alias or the element's alias built from the alias format. If `alias` is not found,
+    /// `NecError::NameNotFound` is returned.
+    ///
+    /// # Arguments
+    /// * `alias` - Alias to remove
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.add_alias(0, "H").unwrap();
+    ///
+    /// assert_eq!(molecule.remove_alias("H"), Ok(0));
+    /// assert!(!molecule.contains_name("H"));
+    /// assert!(molecule.remove_alias("H").is_err());
+    /// ```
+    pub fn remove_alias<T: Into<K>>(&mut self, alias: T) -> Result<usize, NecError<K>> {
+        let alias = alias.into();
+        let key = self.normalizer.normalize(&alias);
+        let index = match self.index_of_alias(&*key) {
+            Some(index) => index,
+            None => return Err(NecError::NameNotFound(alias)),
+        };
+
+        if let Some(ref mut aliases) = self.aliases {
+            aliases.map.remove_index(&key, index);
+        }
+
+        let (bundle, normalizer) = (&mut self.list[index], &self.normalizer);
+        if bundle.alias.as_ref().map(|a| normalizer.normalize(a)) == Some(key.clone()) {
+            bundle.alias = None;
+        } else {
+            bundle.aliases.retain(|a| normalizer.normalize(a) != key);
+        }
+        Ok(index)
+    }
+
+    /// Returns the aliases of the element at `index`: its alias built from the alias format if any, then
+    /// its extra aliases. If `index` is out of bounds, `None` is returned.
+    ///
+    /// # Arguments
+    /// * `index` - Element index
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::alias::hash_suffix;
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::with_alias_format(hash_suffix);
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.add_alias(0, "H1").unwrap();
+    ///
+    /// assert_eq!(water.aliases_of(0).unwrap(), vec!["Hydrogen#0", "H1"]);
+    /// assert!(water.aliases_of(1).is_none());
+    /// ```
+    pub fn aliases_of(&self, index: usize) -> Option<Vec<&K>> {
+        let bundle = self.get(index)?;
+        Some(bundle.alias.iter().chain(&bundle.aliases).collect())
+    }
+
+    /// Adds a secondary index named `name`, mapping the keys returned by `extract` to the elements they were
+    /// extracted from. An index with the same name is replaced. Elements can then be looked up by key with
+    /// `get_by_index()`.
+    ///
+    /// Indexes follow the changes of the collection. Mutable borrows of elements through the collection's
+    /// methods flag indexes as outdated, and they are rebuilt once, by the next change or lookup.
+    /// `get_indexed_mut()` keeps them up to date instead. Writes through the `list` field are not tracked.
+    ///
+    /// # Arguments
+    /// * `name` - Index name
+    /// * `extract` - Function returning the key of an element
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.add_index("by_proton", |a: &Atom| a.proton);
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// assert_eq!(water.get_by_index("by_proton", &1_u8).unwrap().len(), 2);
+    /// assert_eq!(water.get_by_index("by_proton", &8_u8).unwrap()[0].neutron, 8);
+    /// ```
+    pub fn add_index<Key, F>(&mut self, name: &str, extract: F)
+    where
+        Element: 'static,
+        Key: Hash + Eq + Clone + Send + Sync + 'static,
+        F: Fn(&Element) -> Key + Send + Sync + 'static,
+    {
+        self.refresh_indexes();
+        let mut index = KeyIndex {
+            extract: Arc::new(extract),
+            map: HashMap::new(),
+        };
+        for (i, e) in self.list.iter().enumerate() {
+            if !self.handles.is_dead(i) {
+                index.add(&e.elem, i);
+            }
+        }
+
+        self.secondary.insert(name, Box::new(index));
+    }
+
+    /// Removes the secondary index named `name`, and returns true if it existed.
+    ///
+    /// # Arguments
+    /// * `name` - Index name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.add_index("by_proton", |a: &Atom| a.proton);
+    ///
+    /// assert!(water.remove_index("by_proton"));
+    /// assert!(!water.remove_index("by_proton"));
+    /// ```
+    pub fn remove_index(&mut self, name: &str) -> bool {
+        self.secondary.remove_index(name)
+    }
+
+    /// Returns the elements whose key in the secondary index `name` is `key`, in the collection's order.
+    ///
+    /// If there is no index named `name`, `NecError::IndexNotFound` is returned. If its keys are not of type
+    /// `Key`, `NecError::WrongKeyType` is returned: integer literals must then be given the keys' type, like
+    /// `&1_u8`.
+    ///
+    /// # Arguments
+    /// * `name` - Index name
+    /// * `key` - Key to look for
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::error::NecError;
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.add_index("by_proton", |a: &Atom| a.proton);
+    ///
+    /// water.get_by_name_mut("Oxygen").unwrap()[0].proton = 9;
+    /// assert!(water.get_by_index("by_proton", &8_u8).unwrap().is_empty());
+    /// assert_eq!(water.get_by_index("by_proton", &9_u8).unwrap()[0].neutron, 8);
+    ///
+    /// assert_eq!(water.get_by_index("by_proton", &9).err(), Some(NecError::WrongKeyType("by_proton".to_string())));
+    /// assert_eq!(water.get_by_index("by_mass", &9_u8).err(), Some(NecError::IndexNotFound("by_mass".to_string())));
+    /// ```
+    pub fn get_by_index<Key: Hash + Eq + 'static>(
+        &self,
+        name: &str,
+        key: &Key,
+    ) -> Result<Vec<&Element>, NecError<K>>
+    where
+        Element: 'static,
+    {
+        let handles = &self.handles;
+        let live = self
+            .list
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| !handles.is_dead(i))
+            .map(|(i, e)| (i, &e.elem));
+        let indexes = self.secondary.lookup(name, key, live)?;
+        Ok(indexes.iter().map(|&i| &self.list[i].elem).collect())
+    }
+
+    /// Returns a guard giving mutable access to the element at `index`, or `None` if out of bounds. The
+    /// element's keys in the secondary indexes are updated when the guard is dropped.
+    ///
+    /// # Arguments
+    /// * `index` - Element index
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.add_index("by_proton", |a: &Atom| a.proton);
+    ///
+    /// water.get_indexed_mut(0).unwrap().proton = 2;
+    /// assert!(water.get_by_index("by_proton", &1_u8).unwrap().is_empty());
+    /// assert_eq!(water.get_by_index("by_proton", &2_u8).unwrap()[0].neutron, 0);
+    /// ```
+    pub fn get_indexed_mut(
+        &mut self,
+        index: usize,
+    ) -> Option<IndexedMut<'_, Element, Indexes, K, M, N>> {
+        if index >= self.list.len() || self.handles.is_dead(index) {
+            return None;
+        }
+        self.refresh_indexes();
+        self.secondary.remove(&self.list[index].elem, index);
+        Some(IndexedMut {
+            collection: self,
+            index,
+        })
+    }
+    /// Returns the handle of the element at `index`, or `None` if out of bounds. The handle keeps referring
+    /// to this element until it's removed or replaced, even if it's moved.
+    ///
+    /// # Arguments
+    /// * `index` - Element index
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    ///
+    /// let o = water.handle_of(1).unwrap();
+    /// water.reverse();
+    /// assert_eq!(water.index_of_handle(o), Some(0));
+    /// assert!(water.handle_of(2).is_none());
+    /// ```
+    pub fn handle_of(&self, index: usize) -> Option<Handle> {
+        self.handles.get(index)
+    }
+
+    /// Returns the index of the element referred to by `handle`, or `None` if the handle is stale.
+    ///
+    /// # Arguments
+    /// * `handle` - Element handle
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// let h = water.push_handle("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.insert(0, "Oxygen", Atom{ proton:8, neutron:8 });
+    /// assert_eq!(water.index_of_handle(h), Some(1));
+    ///
+    /// water.remove(1);
+    /// assert!(water.index_of_handle(h).is_none());
+    /// ```
+    pub fn index_of_handle(&self, handle: Handle) -> Option<usize> {
+        self.handles.index_of(handle)
+    }
+
+    /// Returns a reference on the element referred to by `handle`, or `None` if the handle is stale.
+    ///
+    /// # Arguments
+    /// * `handle` - Element handle
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// let h = water.push_handle("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// water.swap_remove(0);
+    /// assert_eq!(water.get_by_handle(h).unwrap().neutron, 1);
+    /// water.clear();
+    /// assert!(water.get_by_handle(h).is_none());
+    /// ```
+    pub fn get_by_handle(&self, handle: Handle) -> Option<&Element> {
+        let index = self.handles.index_of(handle)?;
+        Some(&self.list[index].elem)
+    }
+
+    /// Returns a mutable reference on the element referred to by `handle`, or `None` if the handle is stale.
+    ///
+    /// # Arguments
+    /// * `handle` - Element handle
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// let h = water.push_handle("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    ///
+    /// water.sort_by_key(|a| a.proton);
+    /// water.get_by_handle_mut(h).unwrap().neutron = 1;
+    /// assert_eq!(water.get_by_name("Hydrogen").unwrap()[0].neutron, 1);
+    /// ```
+    pub fn get_by_handle_mut(&mut self, handle: Handle) -> Option<&mut Element> {
+        let index = self.handles.index_of(handle)?;
+        Some(self.elem_mut(index))
+    }
+    /// Removes the element at `index` by leaving a tombstone in its place, and returns false if `index` is out
+    /// of bounds or is already a tombstone. Unlike `remove()`, no other index is shifted: the element is only
+    /// unindexed, and skipped by lookups and iterations. It's dropped when the collection is compacted.
+    ///
+    /// Tombstones keep their positions in `list`, and are counted by `len()` but not by `live_len()`. They move
+    /// along with the other elements, and `get()` returns `None` for them. Methods removing an element by index,
+    /// or swapping it, panic when given a tombstone. Nothing is compacted behind the caller's back: tombstones
+    /// are only dropped by `compact()`, or when the range they're in is removed, like with `truncate()`.
+    ///
+    /// # Arguments
+    /// * `index` - Element index
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// assert!(water.tombstone(0));
+    /// assert!(!water.tombstone(0));
+    /// assert_eq!(water.live_len(), 2);
+    /// assert!(water.get(0).is_none());
+    /// assert_eq!(water.get_by_name("Hydrogen").unwrap()[0].neutron, 1);
+    /// assert_eq!(water[1].name, "Oxygen");
+    /// assert_eq!(water.len(), 3);
+    /// ```
+    pub fn tombstone(&mut self, index: usize) -> bool {
+        if index >= self.list.len() || self.handles.is_dead(index) {
+            return false;
+        }
+        self.refresh_indexes();
+
+        let e = &self.list[index];
+        self.hmap
+            .remove_index(&self.normalizer.normalize(&e.name), index);
+        adjust_aliases(&mut self.aliases, &self.normalizer, e, |m, a| {
+            m.remove_index(a, index)
+        });
+        self.secondary.remove(&e.elem, index);
+        self.handles.kill(index);
+        true
+    }
+
+    /// Removes all elements named or aliased `name` by leaving tombstones in their places, and returns how many
+    /// were removed. See `tombstone()`.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// assert_eq!(water.tombstone_by_name("Hydrogen"), 2);
+    /// assert_eq!(water.tombstone_by_name("Hydrogen"), 0);
+    /// assert_eq!(water.iter().map(|e| &e.name).collect::<Vec<_>>(), vec!["Oxygen"]);
+    /// ```
+    pub fn tombstone_by_name<Q>(&mut self, name: &Q) -> usize
+    where
+        M: Lookup<Q, Indexes>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let indexes = match self.lookup(name) {
+            Some(indexes) => indexes.as_slice().to_vec(),
+            None => return 0,
+        };
+
+        // last ones first, so that removing them from the name's sorted indexes moves nothing
+        for &index in indexes.iter().rev() {
+            self.tombstone(index);
+        }
+        indexes.len()
+    }
+
+    /// Returns the number of tombstones in the collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.tombstone(0);
+    /// assert_eq!(molecule.tombstones(), 1);
+    /// ```
+    pub fn tombstones(&self) -> usize {
+        self.handles.dead()
+    }
+
+    /// Returns the ratio of tombstones in `list`, between 0.0 and 1.0, so callers know when to call `compact()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// for i in 0..10 {
+    ///     molecule.push(&format!("Atom{}",i), Atom{ proton:i, neutron:i });
+    /// }
+    ///
+    /// molecule.tombstone(2);
+    /// molecule.tombstone(5);
+    /// assert_eq!(molecule.fragmentation(), 0.2);
+    /// molecule.compact();
+    /// assert_eq!(molecule.fragmentation(), 0.0);
+    /// ```
+    pub fn fragmentation(&self) -> f64 {
+        if self.list.is_empty() {
+            0.0
+        } else {
+            self.handles.dead() as f64 / self.list.len() as f64
+        }
+    }
+
+    /// Drops the tombstones of the collection, rebuilding `list` and `hmap` in one pass. The remaining elements
+    /// keep their order, and their handles. Does nothing if there is no tombstone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// water.tombstone(0);
+    /// water.compact();
+    /// assert_eq!(water.list.len(), 2);
+    /// assert_eq!(water[0].name, "Oxygen");
+    /// assert_eq!(water[1].name, "Hydrogen");
+    /// ```
+    pub fn compact(&mut self) {
+        if self.handles.dead() > 0 {
+            self.reorder(|list| list.retain(|&(_, slot)| slot != TOMBSTONE));
+        }
+    }
+
+    /// Adds an item at the end of the collection, and returns the element displaced if any.
+    ///
+    /// For a UNEC, if `name` is already in the collection, the collection's duplicate policy is applied:
+    /// * `KeepLast` (default): the element is replaced in place, and the replaced one is returned
+    /// * `KeepFirst`: the collection is not changed, and the new element is returned
+    /// * `Merge`: the new element is merged into the existing one, and `None` is returned
+    /// * `Error`: this method panics. Use `try_push()` to get an error instead.
+    ///
+    /// Use `push_handle()` to get the handle of the element instead.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    /// * `element` - Element structure
+    ///
+    /// # Panics
+    ///
+    /// Panics if the duplicate policy is `Error` and `name` is already in the collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// for i in 0..10_u8 {
+    ///     assert!(molecule.push(&format!("Atom{}",i), Atom{ proton:i, neutron:i }).is_none());
+    /// }
+    ///
+    /// for i in 0..10_u8 {
+    ///     let old = molecule.push(&format!("Atom{}",i), Atom{ proton:i, neutron:i+1 }).unwrap();
+    ///     assert_eq!(old.elem.neutron, i);
+    /// }
+    /// assert_eq!(molecule.len(), 10);
+    ///
+    /// for i in 0..10_u8 {
+    ///     assert_eq!(molecule[i as usize].elem.proton, i);
+    /// }
+    /// ```
+    pub fn push<T: Into<K>>(
+        &mut self,
+        name: T,
+        element: Element,
+    ) -> Option<ElementBundle<Element, K>> {
+        self.push_bundle(ElementBundle {
+            elem: element,
+            name: name.into(),
+            alias: None,
+            aliases: Vec::new(),
+        })
+    }
+
+    /// Adds an item at the end of the collection like `push()`, and returns the handle of the element now
+    /// named `name`. For a UNEC, if `name` is already in the collection, the duplicate policy is applied and the
+    /// element displaced, if any, is dropped: the handle is then the one of the element kept in place, which is
+    /// a new one if it was replaced.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    /// * `element` - Element structure
+    ///
+    /// # Panics
+    ///
+    /// Panics if the duplicate policy is `Error` and `name` is already in the collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// let h = molecule.push_handle("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.insert(0, "Helium", Atom{ proton:2, neutron:2 });
+    /// assert_eq!(molecule.get_by_handle(h).unwrap().proton, 1);
+    ///
+    /// let h2 = molecule.push_handle("Hydrogen", Atom{ proton:1, neutron:1 });
+    /// assert!(molecule.get_by_handle(h).is_none());
+    /// assert_eq!(molecule.index_of_handle(h2), Some(1));
+    /// ```
+    pub fn push_handle<T: Into<K>>(&mut self, name: T, element: Element) -> Handle {
+        let bundle = ElementBundle {
+            elem: element,
+            name: name.into(),
+            alias: None,
+            aliases: Vec::new(),
+        };
+        let index = match self.already_in(&self.normalizer.normalize(&bundle.name)) {
+            Some(index) => {
+                let policy = self.policy.clone();
+                self.resolve_duplicate(index, bundle, &policy)
+                    .unwrap_or_else(|_| panic!("{}", DUPLICATE_PANIC));
+                index
+            }
+            None => self.push_new_bundle(bundle),
+        };
+        self.handles.get(index).unwrap()
+    }
+
+    /// Adds an item at the end of the collection, and returns its handle. For a UNEC, if `name` is already in
+    /// the collection, the collection is not changed and `NecError::DuplicateName` is returned, whatever the
+    /// duplicate policy.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    /// * `element` - Element structure
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// let h = molecule.try_push("Hydrogen", Atom{ proton:1, neutron:0 }).unwrap();
+    /// assert!(molecule.try_push("Hydrogen", Atom{ proton:1, neutron:1 }).is_err());
+    /// assert_eq!(molecule.get_by_handle(h).unwrap().neutron, 0);
+    /// ```
+    pub fn try_push<T: Into<K>>(
+        &mut self,
+        name: T,
+        element: Element,
+    ) -> Result<Handle, NecError<K>> {
+        self.push_with_policy(name, element, DuplicatePolicy::Error)?;
+        Ok(self.handles.get(self.list.len() - 1).unwrap())
+    }
+
+    /// Adds an item at the end of the collection, using `policy` instead of the collection's duplicate
+    /// policy. Returns the element displaced if any, like `push()`, or an error if the policy is `Error`
+    /// and `name` is already in the collection.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    /// * `element` - Element structure
+    /// * `policy` - Duplicate policy for this call
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    /// use nec::policy::DuplicatePolicy;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    ///
+    /// let rejected = molecule
+    ///     .push_with_policy("Hydrogen", Atom{ proton:1, neutron:1 }, DuplicatePolicy::KeepFirst)
+    ///     .unwrap()
+    ///     .unwrap();
+    /// assert_eq!(rejected.elem.neutron, 1);
+    /// assert_eq!(molecule["Hydrogen"].elem.neutron, 0);
+    /// ```
+    pub fn push_with_policy<T: Into<K>>(
+        &mut self,
+        name: T,
+        element: Element,
+        policy: DuplicatePolicy<Element>,
+    ) -> Result<Option<ElementBundle<Element, K>>, NecError<K>> {
+        self.push_bundle_with_policy(
+            ElementBundle {
+                elem: element,
+                name: name.into(),
+                alias: None,
+                aliases: Vec::new(),
+            },
+            policy,
+        )
+    }
+
+    // adds an already built bundle, following the push() semantics
+    fn push_bundle(
+        &mut self,
+        bundle: ElementBundle<Element, K>,
+    ) -> Option<ElementBundle<Element, K>> {
+        let policy = self.policy.clone();
+        self.push_bundle_with_policy(bundle, policy)
+            .unwrap_or_else(|_| panic!("{}", DUPLICATE_PANIC))
+    }
+
+    fn push_bundle_with_policy(
+        &mut self,
+        bundle: ElementBundle<Element, K>,
+        policy: DuplicatePolicy<Element>,
+    ) -> Result<Option<ElementBundle<Element, K>>, NecError<K>> {
+        let found = self.already_in(&self.normalizer.normalize(&bundle.name));
+        match found {
+            // if name is already in our list, apply policy
+            Some(index) => self.resolve_duplicate(index, bundle, &policy),
+            // if not, just add the element
+            None => {
+                self.push_new_bundle(bundle);
+                Ok(None)
+            }
+        }
+    }
+
+    // adds a bundle whose name is not already in a UNEC at the end of the collection, giving it a new alias
+    // without extra ones, and returns its index
+    pub(crate) fn push_new_bundle(&mut self, mut bundle: ElementBundle<Element, K>) -> usize {
+        // add index in the hash
+        let index = self.list.len();
+        bundle.alias = self.new_alias(&bundle.name);
+        bundle.aliases.clear();
+        self.hmap
+            .add_element(&self.normalizer.normalize(&bundle.name), index);
+        adjust_aliases(&mut self.aliases, &self.normalizer, &bundle, |m, a| {
+            m.add_element(a, index)
+        });
+        self.refresh_indexes();
+        self.secondary.add(&bundle.elem, index);
+        self.handles.insert(index);
+
+        // add element
+        self.list.push(bundle);
+        index
+    }
+
+    // returns the index of the element of a UNEC already using `key`, as a name or as an alias
+    fn already_in(&mut self, key: &K) -> Option<usize> {
+        self.hmap
+            .already_in(key)
+            .or_else(|| self.aliases.as_mut()?.map.already_in(key))
+    }
+
+    // returns the indexes of the elements named or aliased `name`
+    pub(crate) fn lookup<Q>(&self, name: &Q) -> Option<Cow<'_, Indexes>>
+    where
+        M: Lookup<Q, Indexes>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let key = self.normalizer.normalize(name);
+        self.hmap
+            .get_indexes(&*key)
+            .or_else(|| self.aliases.as_ref()?.map.get_indexes(&*key))
+    }
+
+    // returns the index of the `n`-th element named or aliased `name`, or of the `n`-th one from the end if
+    // `back` is true, without computing the indexes of the others
+    pub(crate) fn lookup_nth<Q>(&self, name: &Q, n: usize, back: bool) -> Option<usize>
+    where
+        M: Lookup<Q, Indexes>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let key = self.normalizer.normalize(name);
+        let map = Some(&self.hmap)
+            .into_iter()
+            .chain(self.aliases.as_ref().map(|aliases| &aliases.map))
+            .find(|map| map.count_indexes(&*key) > 0)?;
+        let n = if back {
+            map.count_indexes(&*key).checked_sub(n + 1)?
+        } else {
+            n
+        };
+        map.nth_index(&*key, n)
+    }
+
+    // returns an alias for a new element named `name`, or None if the collection has no alias format
+    fn new_alias(&self, name: &K) -> Option<K> {
+        let count = self.hmap.count_indexes(&self.normalizer.normalize(name));
+        self.next_alias(name, count, None)
+    }
+
+    // returns the first alias of `name` not used yet, nor in `taken` if given, trying numbers from `from`. As
+    // numbers start from the count of elements named `name`, already used numbers are skipped once some of them
+    // are removed
+    fn next_alias(&self, name: &K, from: usize, taken: Option<&M>) -> Option<K> {
+        let aliases = self.aliases.as_ref()?;
+        let format = aliases.format?;
+        (from..).map(|n| format(name, n)).find(|alias| {
+            let key = self.normalizer.normalize(alias);
+            aliases.map.get_indexes(&key).is_none()
+                && taken.is_none_or(|taken| taken.get_indexes(&key).is_none())
+        })
+    }
+
+    // applies the duplicate policy when bundle's name is already used by the element at index
+    fn resolve_duplicate(
+        &mut self,
+        index: usize,
+        mut bundle: ElementBundle<Element, K>,
+        policy: &DuplicatePolicy<Element>,
+    ) -> Result<Option<ElementBundle<Element, K>>, NecError<K>> {
+        match *policy {
+            DuplicatePolicy::KeepFirst => Ok(Some(bundle)),
+            DuplicatePolicy::KeepLast => {
+                // replace in hmap and replace the element struct, keeping the name as first given,
+                // as well as the aliases
+                bundle.name = self.list[index].name.clone();
+                bundle.alias = self.list[index].alias.take();
+                bundle.aliases = ::std::mem::take(&mut self.list[index].aliases);
+                self.hmap
+                    .replace_element(&self.normalizer.normalize(&bundle.name), index);
+                self.refresh_indexes();
+                self.secondary.remove(&self.list[index].elem, index);
+                self.secondary.add(&bundle.elem, index);
+                self.handles.renew(index);
+                Ok(Some(::std::mem::replace(&mut self.list[index], bundle)))
+            }
+            DuplicatePolicy::Error => Err(NecError::DuplicateName(bundle.name)),
+            DuplicatePolicy::Merge(ref merge) => {
+                self.refresh_indexes();
+                self.secondary.remove(&self.list[index].elem, index);
+                merge(&mut self.list[index].elem, bundle.elem);
+                self.secondary.add(&self.list[index].elem, index);
+                Ok(None)
+            }
+        }
+    }
+
+    // finds where each bundle goes when adding them at the end of the collection like push() does, without
+    // changing the collection: for a UNEC, names already in the collection or repeated among the bundles follow
+    // `policy`, and with `Error` the first of them is returned as an error. Extra aliases of the bundles are kept
+    // unless already used, like add_alias() does
+    fn plan_bundles<'b, I>(
+        &mut self,
+        bundles: I,
+        policy: &DuplicatePolicy<Element>,
+    ) -> Result<Vec<Destination<K>>, NecError<K>>
+    where
+        I: Iterator<Item = &'b ElementBundle<Element, K>>,
+        Element: 'b,
+        K: 'b,
+        M: Default,
+    {
+        // names and aliases of the bundles added at the end, indexed by their rank among them
+        let mut names = M::default();
+        let mut aliases = M::default();
+        let mut plan = Vec::new();
+        let mut added = 0;
+
+        for bundle in bundles {
+            let key = self.normalizer.normalize(&bundle.name);
+            let found = match self.already_in(&key) {
+                Some(index) => Some(Destination::Existing(index)),
+                None => names
+                    .already_in(&key)
+                    .or_else(|| aliases.already_in(&key))
+                    .map(Destination::Added),
+            };
+            let destination = match found {
+                Some(_) if matches!(policy, DuplicatePolicy::Error) => {
+                    return Err(NecError::DuplicateName(bundle.name.clone()));
+                }
+                Some(destination) => destination,
+                None => {
+                    let count = self.hmap.count_indexes(&key) + names.count_indexes(&key);
+                    let alias = self.next_alias(&bundle.name, count, Some(&aliases));
+                    names.add_element(&key, added);
+                    if let Some(ref alias) = alias {
+                        aliases.add_element(&self.normalizer.normalize(alias), added);
+                    }
+
+                    let mut extra = Vec::new();
+                    for alias in &bundle.aliases {
+                        let key = self.normalizer.normalize(alias);
+                        let used = self.hmap.already_in(&key).is_some()
+                            || self
+                                .aliases
+                                .as_ref()
+                                .is_some_and(|aliases| aliases.map.get_indexes(&key).is_some())
+                            || names.already_in(&key).is_some()
+                            || aliases.get_indexes(&key).is_some();
+                        if !used {
+                            aliases.add_element(&key, added);
+                            extra.push(alias.clone());
+                        }
+                    }
+                    added += 1;
+                    Destination::New(alias, extra)
+                }
+            };
+            plan.push(destination);
+        }
+        Ok(plan)
+    }
+
+    // adds bundles at the end of the collection following the plan given by plan_bundles(), then indexes the
+    // new elements in a single pass
+    fn add_bundles<I>(
+        &mut self,
+        bundles: I,
+        plan: Vec<Destination<K>>,
+        policy: DuplicatePolicy<Element>,
+    ) where
+        I: IntoIterator<Item = ElementBundle<Element, K>>,
+        M: Default,
+    {
+        let mut added: Vec<ElementBundle<Element, K>> = Vec::new();
+        for (mut bundle, destination) in bundles.into_iter().zip(plan) {
+            match destination {
+                Destination::Existing(index) => {
+                    // the plan has no destination onto an element with the Error policy
+                    let _ = self.resolve_duplicate(index, bundle, &policy);
+                }
+                Destination::Added(n) => match policy {
+                    DuplicatePolicy::KeepLast => added[n].elem = bundle.elem,
+                    DuplicatePolicy::Merge(ref merge) => merge(&mut added[n].elem, bundle.elem),
+                    DuplicatePolicy::KeepFirst | DuplicatePolicy::Error => {}
+                },
+                Destination::New(alias, extra) => {
+                    if !extra.is_empty() {
+                        self.aliases_mut();
+                    }
+                    bundle.alias = alias;
+                    bundle.aliases = extra;
+                    added.push(bundle);
+                }
+            }
+        }
+
+        if !added.is_empty() {
+            self.list.extend(added);
+            self.reindex();
+        }
+    }
+
+    // rebuilds the whole hmap from the list, in a single pass
+    fn reindex(&mut self) {
+        self.hmap.clear();
+        for (i, e) in self.list.iter().enumerate() {
+            if !self.handles.is_dead(i) {
+                self.hmap
+                    .add_element(&self.normalizer.normalize(&e.name), i);
+            }
+        }
+        self.reindex_aliases();
+        self.rebuild_indexes();
+        self.handles.resize(self.list.len());
+    }
+
+    // applies a change of the whole list, with each element paired with its handle's slot or TOMBSTONE, then
+    // rebuilds the hmap. Handles of elements no longer in the list become stale
+    fn reorder<R, F>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut Vec<(ElementBundle<Element, K>, usize)>) -> R,
+    {
+        let mut list = self.handles.attach(::std::mem::take(&mut self.list));
+        let r = f(&mut list);
+        self.list = self.handles.detach(list);
+        self.reindex();
+        r
+    }
+
+    // rebuilds the secondary indexes if elements have been mutably borrowed since they were last updated
+    fn refresh_indexes(&mut self) {
+        if self.secondary.is_stale() {
+            self.rebuild_indexes();
+        }
+    }
+
+    // rebuilds the secondary indexes from the live elements
+    fn rebuild_indexes(&mut self) {
+        let handles = &self.handles;
+        self.secondary.rebuild(
+            self.list
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| !handles.is_dead(i))
+                .map(|(i, e)| (i, &e.elem)),
+        );
+    }
+
+    // panics if the element at index is a tombstone
+    fn assert_live(&self, index: usize) {
+        assert!(
+            !self.handles.is_dead(index),
+            "index (is {}) should not be a tombstone",
+            index
+        );
+    }
+
+    // returns a mutable reference on the element at index, flagging secondary indexes as stale
+    pub(crate) fn elem_mut(&mut self, index: usize) -> &mut Element {
+        self.secondary.touch();
+        &mut self.list[index].elem
+    }
+
+    // rebuilds the whole aliases map from the list, in a single pass
+    fn reindex_aliases(&mut self) {
+        if let Some(ref mut aliases) = self.aliases {
+            aliases.map.clear();
+            for (i, e) in self.list.iter().enumerate() {
+                if self.handles.is_dead(i) {
+                    continue;
+                }
+                for alias in e.alias.iter().chain(&e.aliases) {
+                    aliases
+                        .map
+                        .add_element(&self.normalizer.normalize(alias), i);
+                }
+            }
+        }
+    }
+
+    /// Adds an item at the end of the collection, using its own name.
+    ///
+    /// # Arguments
+    /// * `element` - Element structure
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    /// use nec::nameable::Nameable;
+    ///
+    /// struct Atom { symbol: &'static str, proton: u8, };
+    /// impl Nameable for Atom {
+    ///     fn name(&self) -> &str {
+    ///         self.symbol
+    ///     }
+    /// }
+    ///
+    /// let mut water = DNEC::<Atom>::new();
+    /// water.push_named(Atom{ symbol: "H", proton:1 });
+    /// water.push_named(Atom{ symbol: "H", proton:1 });
+    /// water.push_named(Atom{ symbol: "O", proton:8 });
+    ///
+    /// assert_eq!(water.get_by_name("H").unwrap().len(), 2);
+    /// ```
+    pub fn push_named(&mut self, element: Element)
+    where
+        Element: Nameable,
+        K: for<'a> From<&'a str>,
+    {
+        let name = K::from(element.name());
+        self.push(name, element);
+    }
+
+    /// Removes an element from the collection by providing its index.
+    ///
+    /// # Arguments
+    /// * `index` - Element index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds or is a tombstone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// for i in 0..10 {
+    ///     molecule.push(&format!("Atom{}",i), Atom{ proton:i, neutron:i });
+    /// }
+    ///
+    /// let removed = molecule.remove(5);
+    /// assert_eq!(removed.name, "Atom5");
+    /// assert_eq!(molecule.len(),9);
+    /// assert_eq!(molecule.get_name(0).unwrap(),"Atom0");
+    /// assert_eq!(molecule.get_name(8).unwrap(),"Atom9");
+    /// assert_eq!(molecule["Atom9"].elem.proton, 9);
+    ///
+    /// // removing the last element is fine too
+    /// molecule.remove(8);
+    /// assert!(!molecule.contains_name("Atom9"));
+    /// ```
+    pub fn remove(&mut self, index: usize) -> ElementBundle<Element, K> {
+        self.assert_live(index);
+        self.refresh_indexes();
+
+        // delete from main list
+        let e = self.list.remove(index);
+
+        // remove relevant indexes from hashmap, and shift the ones above
+        self.hmap
+            .delete_element(&self.normalizer.normalize(&e.name), index);
+        adjust_aliases(&mut self.aliases, &self.normalizer, &e, |m, a| {
+            m.remove_index(a, index)
+        });
+        if let Some(ref mut aliases) = self.aliases {
+            aliases.shift(index, false);
+        }
+        self.secondary.remove(&e.elem, index);
+        self.secondary.shift(index, false);
+        self.handles.remove(index);
+
+        e
+    }
+
+    /// Removes an element from the collection by providing its index, and returns it. The removed element is
+    /// replaced by the last element of the collection.
+    ///
+    /// This does not preserve ordering, but is O(1): only the indexes of the removed and of the moved
+    /// elements are updated. The last element can be a tombstone, which is then moved like the others.
+    ///
+    /// # Arguments
+    /// * `index` - Element index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds or is a tombstone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// for i in 0..10 {
+    ///     molecule.push(&format!("Atom{}",i), Atom{ proton:i, neutron:i });
+    /// }
+    ///
+    /// let removed = molecule.swap_remove(2);
+    /// assert_eq!(removed.name, "Atom2");
+    /// assert_eq!(molecule.len(),9);
+    /// assert_eq!(molecule.get_name(2).unwrap(),"Atom9");
+    /// assert_eq!(molecule["Atom9"].elem.proton, 9);
+    /// ```
+    pub fn swap_remove(&mut self, index: usize) -> ElementBundle<Element, K> {
+        self.assert_live(index);
+        let last = self.list.len().saturating_sub(1);
+        self.refresh_indexes();
+
+        // delete from main list
+        let e = self.list.swap_remove(index);
+        self.hmap
+            .remove_index(&self.normalizer.normalize(&e.name), index);
+        adjust_aliases(&mut self.aliases, &self.normalizer, &e, |m, a| {
+            m.remove_index(a, index)
+        });
+        self.secondary.remove(&e.elem, index);
+        self.handles.swap_remove(index);
+
+        // last element is now at index
+        if index != last && !self.handles.is_dead(index) {
+            self.hmap.move_index(
+                &self.normalizer.normalize(&self.list[index].name),
+                last,
+                index,
+            );
+            adjust_aliases(
+                &mut self.aliases,
+                &self.normalizer,
+                &self.list[index],
+                |m, a| m.move_index(a, last, index),
+            );
+            self.secondary.remove(&self.list[index].elem, last);
+            self.secondary.add(&self.list[index].elem, index);
+        }
+
+        e
+    }
+
+    /// Removes the last element from the collection and returns it, or `None` if it is empty. Tombstones at the
+    /// end of the collection are dropped first: the element returned is then the last one not removed, and `len()`
+    /// decreases by more than one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    ///
+    /// assert_eq!(water.pop().unwrap().name, "Oxygen");
+    /// assert!(!water.contains_name("Oxygen"));
+    /// assert_eq!(water.pop().unwrap().name, "Hydrogen");
+    /// assert_eq!(water.get_by_name("Hydrogen").unwrap().len(), 1);
+    ///
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.tombstone(1);
+    /// assert_eq!(water.pop().unwrap().name, "Hydrogen");
+    /// assert_eq!(water.len(), 0);
+    /// ```
+    pub fn pop(&mut self) -> Option<ElementBundle<Element, K>> {
+        while !self.list.is_empty() && self.handles.is_dead(self.list.len() - 1) {
+            self.list.pop();
+            self.handles.remove(self.list.len());
+        }
+        self.refresh_indexes();
+        let e = self.list.pop()?;
+        let index = self.list.len();
+        self.hmap
+            .remove_index(&self.normalizer.normalize(&e.name), index);
+        adjust_aliases(&mut self.aliases, &self.normalizer, &e, |m, a| {
+            m.remove_index(a, index)
+        });
+        self.secondary.remove(&e.elem, index);
+        self.handles.remove(index);
+        Some(e)
+    }
+
+    /// Retains only the elements specified by the predicate, keeping their order. The `hmap` is
+    /// rebuilt once all elements are visited.
+    ///
+    /// # Arguments
+    /// * `f` - Predicate called on each element bundle
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// for i in 0..10 {
+    ///     molecule.push(&format!("Atom{}",i), Atom{ proton:i, neutron:i });
+    /// }
+    ///
+    /// molecule.retain(|e| e.elem.proton % 2 == 0);
+    /// assert_eq!(molecule.len(), 5);
+    /// assert_eq!(molecule["Atom8"].elem.proton, 8);
+    /// assert!(!molecule.contains_name("Atom9"));
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&ElementBundle<Element, K>) -> bool,
+    {
+        self.reorder(|list| list.retain(|&(ref e, slot)| slot == TOMBSTONE || f(e)));
+    }
+
+    /// Returns an iterator over the elements whose names match the wildcard `pattern`, as `(index, bundle)`
+    /// pairs in collection order. `*` matches any sequence of characters, `?` any single character, and
+    /// `[abc]` any character between brackets. Ranges like `[a-z]` and negations like `[!abc]` are also
+    /// supported. Names and pattern are both normalized before matching.
+    ///
+    /// # Arguments
+    /// * `pattern` - Wildcard pattern
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// let mut sensors = DNEC::<f32>::new();
+    ///
+    /// sensors.push("sensor.kitchen.temp", 21.5);
+    /// sensors.push("sensor.kitchen.humidity", 40.0);
+    /// sensors.push("sensor.garage.temp", 12.0);
+    ///
+    /// let temps: Vec<_> = sensors.find_glob("sensor.*.temp").map(|(i, e)| (i, e.elem)).collect();
+    /// assert_eq!(temps, vec![(0, 21.5), (2, 12.0)]);
+    /// ```
+    pub fn find_glob<'a>(&'a self, pattern: &'a str) -> NecGlob<'a, Element, K, N>
+    where
+        K: Borrow<str>,
+        N: Normalizer<str>,
+    {
+        NecGlob {
+            iter: self.list.iter().enumerate(),
+            handles: &self.handles,
+            pattern: self.normalizer.normalize(pattern),
+            normalizer: &self.normalizer,
+        }
+    }
+
+    /// Returns the number of elements whose names match the wildcard `pattern`. See `find_glob()` for the
+    /// supported wildcards.
+    ///
+    /// # Arguments
+    /// * `pattern` - Wildcard pattern
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    /// molecule.push("Lithium", Atom{ proton:3, neutron:4 });
+    ///
+    /// assert_eq!(molecule.count_glob("H*"), 2);
+    /// assert_eq!(molecule.count_glob("[!H]?thium"), 1);
+    /// ```
+    pub fn count_glob(&self, pattern: &str) -> usize
+    where
+        K: Borrow<str>,
+        N: Normalizer<str>,
+    {
+        self.find_glob(pattern).count()
+    }
+
+    /// Removes the elements whose names match the wildcard `pattern`, and returns them in collection order.
+    /// The order of the remaining elements is kept, and the `hmap` is rebuilt once. See `find_glob()` for the
+    /// supported wildcards.
+    ///
+    /// # Arguments
+    /// * `pattern` - Wildcard pattern
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    /// molecule.push("Lithium", Atom{ proton:3, neutron:4 });
+    ///
+    /// let removed = molecule.remove_glob("*ium");
+    /// assert_eq!(removed.len(), 2);
+    /// assert_eq!(removed[1].name, "Lithium");
+    /// assert_eq!(molecule["Hydrogen"].elem.proton, 1);
+    /// ```
+    pub fn remove_glob(&mut self, pattern: &str) -> Vec<ElementBundle<Element, K>>
+    where
+        K: Borrow<str>,
+        N: Normalizer<str>,
+    {
+        let pattern = self.normalizer.normalize(pattern);
+        let list = self.handles.attach(::std::mem::take(&mut self.list));
+
+        let (removed, kept): (Vec<_>, Vec<_>) = list.into_iter().partition(|&(ref e, slot)| {
+            slot != TOMBSTONE
+                && glob::matches(&pattern, &self.normalizer.normalize(e.name.borrow()))
+        });
+        self.list = self.handles.detach(kept);
+        self.reindex();
+
+        removed.into_iter().map(|(e, _)| e).collect()
+    }
+
+    /// Removes the elements in `range` from the collection and returns them as an iterator. Elements are
+    /// removed even if the iterator is not consumed, and tombstones in `range` are dropped. The `hmap` is
+    /// rebuilt once.
+    ///
+    /// # Arguments
+    /// * `range` - Range of indexes to remove
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if the end point is greater
+    /// than the length of the collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    ///
+    /// let drained: Vec<_> = water.drain(..2).map(|e| e.name).collect();
+    /// assert_eq!(drained, vec!["Hydrogen", "Oxygen"]);
+    /// assert_eq!(water.len(), 1);
+    /// assert!(!water.contains_name("Oxygen"));
+    /// assert_eq!(water.get_by_name("Hydrogen").unwrap().len(), 1);
+    /// ```
+    pub fn drain<R>(&mut self, range: R) -> NecIntoIterator<Element, K>
+    where
+        R: RangeBounds<usize>,
+    {
+        let drained: Vec<_> = self.reorder(|list| {
+            list.drain(range)
+                .filter(|&(_, slot)| slot != TOMBSTONE)
+                .map(|(e, _)| e)
+                .collect()
+        });
+
+        NecIntoIterator {
+            iter: drained.into_iter(),
+        }
+    }
+
+    /// Shortens the collection, keeping the first `len` elements and dropping the rest, tombstones included. If
+    /// `len` is greater than the collection's current length, this has no effect.
+    ///
+    /// # Arguments
+    /// * `len` - Number of elements to keep
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// for i in 0..10 {
+    ///     molecule.push(&format!("Atom{}",i), Atom{ proton:i, neutron:i });
+    /// }
+    ///
+    /// molecule.truncate(3);
+    /// assert_eq!(molecule.len(), 3);
+    /// assert!(molecule.contains_name("Atom2"));
+    /// assert!(!molecule.contains_name("Atom3"));
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.list.len() {
+            return;
+        }
+        self.unindex_tail(len);
+        self.list.truncate(len);
+    }
+
+    /// Splits the collection into two at the given index. Returns a newly allocated collection containing
+    /// the elements in the range `[at, len)`, without their tombstones, while `self` is left with the elements
+    /// `[0, at)`.
+    ///
+    /// # Arguments
+    /// * `at` - Index of the split
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// for i in 0..10 {
+    ///     molecule.push(&format!("Atom{}",i), Atom{ proton:i, neutron:i });
+    /// }
+    ///
+    /// let other = molecule.split_off(6);
+    /// assert_eq!(molecule.len(), 6);
+    /// assert_eq!(other.len(), 4);
+    /// assert_eq!(other["Atom6"].elem.proton, 6);
+    /// assert!(!molecule.contains_name("Atom6"));
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> Self
+    where
+        M: Clone,
+        N: Clone,
+    {
+        assert!(
+            at <= self.list.len(),
+            "`at` split index (is {}) should be <= len (is {})",
+            at,
+            self.list.len()
+        );
+        let live: Vec<bool> = (at..self.list.len())
+            .map(|i| !self.handles.is_dead(i))
+            .collect();
+        self.unindex_tail(at);
+
+        // cloning the emptied map keeps its settings, like a HashMap hasher
+        let mut hmap = self.hmap.clone();
+        hmap.clear();
+
+        let mut other = NamedElementsCollection::from_parts(0, hmap, self.normalizer.clone());
+        other.policy = self.policy.clone();
+        other.aliases = self.aliases.clone();
+        other.secondary = self.secondary.clone();
+        other.list = self
+            .list
+            .split_off(at)
+            .into_iter()
+            .zip(live)
+            .filter(|&(_, live)| live)
+            .map(|(e, _)| e)
+            .collect();
+        other.reindex();
+
+        other
+    }
+
+    /// Moves all the elements of `other` into `self`, leaving `other` empty. For a UNEC, elements
+    /// of `other` whose names are already in `self` follow `self`'s duplicate policy, like `push()` does:
+    /// by default, they replace the existing ones in place. Moved elements keep their extra aliases, except the
+    /// ones already used in `self`. The new elements are indexed in a single pass once all are moved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the duplicate policy is `Error` and a name of `other` is already in `self`. No element is moved
+    /// then.
+    ///
+    /// # Arguments
+    /// * `other` - Collection to move elements from
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    /// let mut other = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:1 });
+    /// other.push("Helium", Atom{ proton:2, neutron:2 });
+    /// other.push("Lithium", Atom{ proton:3, neutron:4 });
+    ///
+    /// molecule.append(&mut other);
+    /// assert!(other.is_empty());
+    /// assert_eq!(molecule.len(), 3);
+    /// assert_eq!(molecule["Helium"].elem.neutron, 2);
+    /// assert_eq!(molecule.get_name(2).unwrap(), "Lithium");
+    /// ```
+    pub fn append(&mut self, other: &mut Self)
+    where
+        M: Default,
+    {
+        let policy = self.policy.clone();
+        let bundles = other
+            .list
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| !other.handles.is_dead(i))
+            .map(|(_, e)| e);
+        let plan = self
+            .plan_bundles(bundles, &policy)
+            .unwrap_or_else(|_| panic!("{}", DUPLICATE_PANIC));
+        let list: Vec<_> = other.drain(..).collect();
+        self.add_bundles(list, plan, policy);
+    }
+
+    // removes from hmap the indexes of all elements from `len`. As these are the last ones,
+    // no other index needs to be shifted
+    fn unindex_tail(&mut self, len: usize) {
+        self.refresh_indexes();
+        for i in (len..self.list.len()).rev() {
+            if self.handles.is_dead(i) {
+                continue;
+            }
+            self.hmap
+                .remove_index(&self.normalizer.normalize(&self.list[i].name), i);
+            adjust_aliases(
+                &mut self.aliases,
+                &self.normalizer,
+                &self.list[i],
+                |m, a| m.remove_index(a, i),
+            );
+            self.secondary.remove(&self.list[i].elem, i);
+        }
+        self.handles.resize(len);
+    }
+
+    /// Sorts the collection by elements' names. This sort is stable: elements having the same name
+    /// keep their relative order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    ///
+    /// molecule.sort_by_name();
+    /// assert_eq!(molecule.get_name(0).unwrap(), "Helium");
+    /// assert_eq!(molecule.get_name(2).unwrap(), "Oxygen");
+    /// assert_eq!(molecule["Oxygen"].elem.proton, 8);
+    /// ```
+    pub fn sort_by_name(&mut self)
+    where
+        K: Ord,
+    {
+        self.reorder(|list| list.sort_by(|a, b| a.0.name.cmp(&b.0.name)));
+    }
+
+    /// Sorts the collection with a key extraction function applied on each element. This sort is stable.
+    ///
+    /// # Arguments
+    /// * `f` - Key extraction function
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    ///
+    /// molecule.sort_by_key(|a| a.proton);
+    /// assert_eq!(molecule.get_name(0).unwrap(), "Hydrogen");
+    /// assert_eq!(molecule["Hydrogen"].elem.proton, 1);
+    /// ```
+    pub fn sort_by_key<Key, F>(&mut self, mut f: F)
+    where
+        Key: Ord,
+        F: FnMut(&Element) -> Key,
+    {
+        self.reorder(|list| list.sort_by_key(|e| f(&e.0.elem)));
+    }
+
+    /// Sorts the collection with a comparator function applied on element bundles. This sort is stable.
+    ///
+    /// # Arguments
+    /// * `compare` - Comparator function
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// water.sort_by(|a, b| b.elem.proton.cmp(&a.elem.proton).then(a.elem.neutron.cmp(&b.elem.neutron)));
+    /// assert_eq!(water.get_name(0).unwrap(), "Oxygen");
+    /// assert_eq!(water.first_by_name("Hydrogen").unwrap().neutron, 0);
+    /// ```
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&ElementBundle<Element, K>, &ElementBundle<Element, K>) -> Ordering,
+    {
+        self.reorder(|list| list.sort_by(|a, b| compare(&a.0, &b.0)));
+    }
+
+    /// Reverses the order of elements in the collection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    ///
+    /// molecule.reverse();
+    /// assert_eq!(molecule.get_name(0).unwrap(), "Helium");
+    /// assert_eq!(molecule["Hydrogen"].elem.proton, 1);
+    /// ```
+    pub fn reverse(&mut self) {
+        self.reorder(|list| list.reverse());
+    }
+
+    /// Swaps two elements in the collection. Only the indexes of those two elements are updated.
+    ///
+    /// # Arguments
+    /// * `a` - Index of the first element
+    /// * `b` - Index of the second element
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` are out of bounds or are tombstones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    /// molecule.push("Lithium", Atom{ proton:3, neutron:4 });
+    ///
+    /// molecule.swap(0, 2);
+    /// assert_eq!(molecule.get_name(0).unwrap(), "Lithium");
+    /// assert_eq!(molecule["Hydrogen"].elem.proton, 1);
+    /// ```
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.assert_live(a);
+        self.assert_live(b);
+        self.refresh_indexes();
+        self.list.swap(a, b);
+        if a == b {
+            return;
+        }
+
+        // elements have been swapped, so they now have the other one's index
+        self.hmap
+            .move_index(&self.normalizer.normalize(&self.list[b].name), a, b);
+        self.hmap
+            .move_index(&self.normalizer.normalize(&self.list[a].name), b, a);
+        adjust_aliases(
+            &mut self.aliases,
+            &self.normalizer,
+            &self.list[b],
+            |m, alias| m.move_index(alias, a, b),
+        );
+        adjust_aliases(
+            &mut self.aliases,
+            &self.normalizer,
+            &self.list[a],
+            |m, alias| m.move_index(alias, b, a),
+        );
+
+        // both elements could have the same keys, so they are all removed before being added back
+        self.secondary.remove(&self.list[b].elem, a);
+        self.secondary.remove(&self.list[a].elem, b);
+        self.secondary.add(&self.list[b].elem, b);
+        self.secondary.add(&self.list[a].elem, a);
+        self.handles.swap(a, b);
+    }
+
+    /// Moves the element at index `from` to index `to`, shifting the elements in between.
+    ///
+    /// # Arguments
+    /// * `from` - Current index of the element
+    /// * `to` - New index of the element
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` or `to` are out of bounds, or if `from` is a tombstone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    /// molecule.push("Lithium", Atom{ proton:3, neutron:4 });
+    ///
+    /// molecule.move_to(0, 2);
+    /// assert_eq!(molecule.get_name(0).unwrap(), "Helium");
+    /// assert_eq!(molecule.get_name(2).unwrap(), "Hydrogen");
+    /// assert_eq!(molecule["Hydrogen"].elem.proton, 1);
+    /// ```
+    pub fn move_to(&mut self, from: usize, to: usize) {
+        assert!(
+            from < self.list.len() && to < self.list.len(),
+            "indexes (are {} and {}) should be < len (is {})",
+            from,
+            to,
+            self.list.len()
+        );
+        self.assert_live(from);
+
+        self.reorder(|list| {
+            if from < to {
+                list[from..=to].rotate_left(1);
+            } else {
+                list[to..=from].rotate_right(1);
+            }
+        });
+    }
+
+    /// Rotates the collection in-place such that the first `n` elements move to the end.
+    ///
+    /// # Arguments
+    /// * `n` - Number of elements to rotate
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    /// molecule.push("Lithium", Atom{ proton:3, neutron:4 });
+    ///
+    /// molecule.rotate_left(1);
+    /// assert_eq!(molecule.get_name(0).unwrap(), "Helium");
+    /// assert_eq!(molecule.get_name(2).unwrap(), "Hydrogen");
+    /// ```
+    pub fn rotate_left(&mut self, n: usize) {
+        self.reorder(|list| list.rotate_left(n));
+    }
+
+    /// Rotates the collection in-place such that the last `n` elements move to the front.
+    ///
+    /// # Arguments
+    /// * `n` - Number of elements to rotate
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    /// molecule.push("Lithium", Atom{ proton:3, neutron:4 });
+    ///
+    /// molecule.rotate_right(1);
+    /// assert_eq!(molecule.get_name(0).unwrap(), "Lithium");
+    /// assert_eq!(molecule["Lithium"].elem.proton, 3);
+    /// ```
+    pub fn rotate_right(&mut self, n: usize) {
+        self.reorder(|list| list.rotate_right(n));
+    }
+}
+
+//-----------------------------------------------------------------------
+// Specializations
+//-----------------------------------------------------------------------
+impl<Element, K, M, N> NamedElementsCollection<Element, usize, K, M, N>
+where
+    M: NameMap<K, usize>,
+    K: Eq + Clone,
+    N: Normalizer<K>,
+{
+    /// Returns a reference on the element named `name`. If `name` is not found, `None` is returned.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    ///
+    /// assert_eq!(molecule.get_by_name("Helium").unwrap().proton, 2);
+    /// assert!(molecule.get_by_name("Lithium").is_none());
+    /// ```
+    pub fn get_by_name<Q>(&self, name: &Q) -> Option<&Element>
+    where
+        M: Lookup<Q, usize>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let index = *self.lookup(name)?;
+        Some(&self.list[index].elem)
+    }
+
+    /// Returns a mutable reference on the element named `name`. If `name` is not found, `None` is returned.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Helium", Atom{ proton:2, neutron:1 });
+    ///
+    /// molecule.get_by_name_mut("Helium").unwrap().neutron = 2;
+    /// assert_eq!(molecule["Helium"].elem.neutron, 2);
+    /// assert!(molecule.get_by_name_mut("Lithium").is_none());
+    /// ```
+    pub fn get_by_name_mut<Q>(&mut self, name: &Q) -> Option<&mut Element>
+    where
+        M: Lookup<Q, usize>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let index = *self.lookup(name)?;
+        Some(self.elem_mut(index))
+    }
+
+    /// Removes the element named `name` from the collection and returns it. If `name` is not found,
+    /// `None` is returned.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    /// molecule.push("Lithium", Atom{ proton:3, neutron:4 });
+    ///
+    /// assert_eq!(molecule.remove_by_name("Helium").unwrap().elem.proton, 2);
+    /// assert!(molecule.remove_by_name("Helium").is_none());
+    /// assert_eq!(molecule["Lithium"].elem.proton, 3);
+    /// ```
+    pub fn remove_by_name<Q>(&mut self, name: &Q) -> Option<ElementBundle<Element, K>>
+    where
+        M: Lookup<Q, usize>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let index = *self.lookup(name)?;
+        Some(self.remove(index))
+    }
+}
+
+impl<Element, K, M, N> NamedElementsCollection<Element, Vec<usize>, K, M, N>
+where
+    M: NameMap<K, Vec<usize>>,
+    K: Eq + Clone,
+    N: Normalizer<K>,
+{
+    /// Removes all elements named `name` from the collection and returns them, in the order they
+    /// were found in the collection. If `name` is not found, an empty vector is returned.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// let v = water.remove_all_by_name("Hydrogen");
+    /// assert_eq!(v.len(), 2);
+    /// assert_eq!(v[1].elem.neutron, 1);
+    /// assert_eq!(water.len(), 1);
+    /// assert_eq!(water.get_by_name("Oxygen").unwrap()[0].proton, 8);
+    /// assert!(water.remove_all_by_name("Hydrogen").is_empty());
+    /// ```
+    pub fn remove_all_by_name<Q>(&mut self, name: &Q) -> Vec<ElementBundle<Element, K>>
+    where
+        M: Lookup<Q, Vec<usize>>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        // indexes are sorted
+        let indexes = match self.hmap.remove_indexes(&*self.normalizer.normalize(name)) {
+            Some(v) => v,
+            None => return Vec::new(),
+        };
+
+        // split the list between kept and removed elements, in one pass
+        let list = self.handles.attach(::std::mem::take(&mut self.list));
+        let mut removed = Vec::with_capacity(indexes.len());
+        let mut kept = Vec::with_capacity(list.len() - indexes.len());
+        let mut to_remove = indexes.iter().peekable();
+
+        for (i, e) in list.into_iter().enumerate() {
+            if to_remove.peek() == Some(&&i) {
+                to_remove.next();
+                removed.push(e.0);
+            } else {
+                kept.push(e);
+            }
+        }
+        self.list = self.handles.detach(kept);
+
+        // each remaining index is shifted by the number of removed elements before it
+        self.hmap.shift_removed(&indexes);
+        self.reindex_aliases();
+        self.rebuild_indexes();
+
+        removed
+    }
+
+    /// Removes the `n`-th element named `name` (starting from 0) from the collection and returns it.
+    /// If `name` is not found or if there are less than `n+1` elements with this name, `None` is returned.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    /// * `n` - Rank of the element among those sharing the same name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// assert_eq!(water.remove_nth_by_name("Hydrogen", 1).unwrap().elem.neutron, 1);
+    /// assert!(water.remove_nth_by_name("Hydrogen", 1).is_none());
+    /// assert_eq!(water.len(), 2);
+    /// ```
+    pub fn remove_nth_by_name<Q>(&mut self, name: &Q, n: usize) -> Option<ElementBundle<Element, K>>
+    where
+        M: Lookup<Q, Vec<usize>>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let index = self.lookup_nth(name, n, false)?;
+        Some(self.remove(index))
+    }
+
+    /// Returns a vector of elements having the same name. If `name`is not found, `None`is returned.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    ///
+    /// let mut v = water.get_by_name("Hydrogen").unwrap();
+    ///
+    /// assert_eq!(v.len(), 2);
+    /// assert_eq!(v[0].proton, 1);
+    /// assert_eq!(v[0].neutron, 0);
+    /// assert_eq!(v[1].proton, 1);
+    /// assert_eq!(v[1].neutron, 0);
+    /// assert!(water.get_by_name("Helium").is_none());
+    /// ```
+    pub fn get_by_name<Q>(&self, name: &Q) -> Option<Vec<&Element>>
+    where
+        M: Lookup<Q, Vec<usize>>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        self.lookup_nth(name, 0, false)?;
+
+        Some(self.iter_by_name(name).collect())
+    }
+
+    /// Returns a vector of mutable references on elements having the same name. If `name`is not found,
+    /// `None`is returned.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// for h in water.get_by_name_mut("Hydrogen").unwrap() {
+    ///     h.neutron = 0;
+    /// }
+    /// assert!(water.iter().filter(|e| e.name == "Hydrogen").all(|e| e.elem.neutron == 0));
+    /// assert!(water.get_by_name_mut("Helium").is_none());
+    /// ```
+    pub fn get_by_name_mut<Q>(&mut self, name: &Q) -> Option<Vec<&mut Element>>
+    where
+        M: Lookup<Q, Vec<usize>>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let indexes = self.lookup(name)?.into_owned();
+        let mut v = Vec::with_capacity(indexes.len());
+
+        // indexes are sorted, so the list can be split further and further
+        self.secondary.touch();
+        let mut rest: &mut [ElementBundle<Element, K>] = &mut self.list;
+        let mut offset = 0;
+
+        for i in indexes {
+            let (_, tail) = ::std::mem::take(&mut rest).split_at_mut(i - offset);
+            let (e, tail) = tail.split_first_mut().unwrap();
+            v.push(&mut e.elem);
+
+            rest = tail;
+            offset = i + 1;
+        }
+
+        Some(v)
+    }
+
+    /// Renames only the `n`-th element named `old_name` (starting from 0), without changing its position in
+    /// the collection. If `old_name` is not found or if there are less than `n+1` elements with this name,
+    /// `NecError::NameNotFound` is returned.
+    ///
+    /// # Arguments
+    /// * `old_name` - Element's current name
+    /// * `n` - Rank of the element among those sharing the same name
+    /// * `new_name` - Element's new name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// assert!(water.rename_nth_by_name("Hydrogen", 1, "Deuterium").is_ok());
+    /// assert_eq!(water.get_name(1).unwrap(), "Deuterium");
+    /// assert_eq!(water.get_by_name("Hydrogen").unwrap().len(), 1);
+    /// assert!(water.rename_nth_by_name("Hydrogen", 1, "Deuterium").is_err());
+    /// ```
+    pub fn rename_nth_by_name<O, T>(
+        &mut self,
+        old_name: O,
+        n: usize,
+        new_name: T,
+    ) -> Result<(), NecError<K>>
+    where
+        O: Into<K>,
+        T: Into<K>,
+    {
+        let old_name = old_name.into();
+        let index = match self
+            .hmap
+            .get_indexes(&*self.normalizer.normalize(&old_name))
+            .and_then(|v| v.get(n).cloned())
+        {
+            Some(index) => index,
+            None => return Err(NecError::NameNotFound(old_name)),
+        };
+
+        self.rename(index, new_name)
+    }
+
+    /// Returns a reference on the `n`-th element named `name` (starting from 0). If `name` is not found or if
+    /// there are less than `n+1` elements with this name, `None` is returned.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    /// * `n` - Rank of the element among those sharing the same name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// assert_eq!(water.nth_by_name("Hydrogen", 1).unwrap().neutron, 1);
+    /// assert!(water.nth_by_name("Hydrogen", 2).is_none());
+    /// ```
+    pub fn nth_by_name<Q>(&self, name: &Q, n: usize) -> Option<&Element>
+    where
+        M: Lookup<Q, Vec<usize>>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let index = self.lookup_nth(name, n, false)?;
+        Some(&self.list[index].elem)
+    }
+
+    /// Returns a mutable reference on the `n`-th element named `name` (starting from 0). If `name` is not found
+    /// or if there are less than `n+1` elements with this name, `None` is returned.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    /// * `n` - Rank of the element among those sharing the same name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    ///
+    /// water.nth_by_name_mut("Hydrogen", 1).unwrap().neutron = 1;
+    /// assert_eq!(water[2].elem.neutron, 1);
+    /// ```
+    pub fn nth_by_name_mut<Q>(&mut self, name: &Q, n: usize) -> Option<&mut Element>
+    where
+        M: Lookup<Q, Vec<usize>>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let index = self.lookup_nth(name, n, false)?;
+        Some(self.elem_mut(index))
+    }
+
+    /// Returns a reference on the first element named `name`. If `name` is not found, `None` is returned.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// assert_eq!(water.first_by_name("Hydrogen").unwrap().neutron, 0);
+    /// assert!(water.first_by_name("Helium").is_none());
+    /// ```
+    pub fn first_by_name<Q>(&self, name: &Q) -> Option<&Element>
+    where
+        M: Lookup<Q, Vec<usize>>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        self.nth_by_name(name, 0)
+    }
+
+    /// Returns a mutable reference on the first element named `name`. If `name` is not found, `None` is returned.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// water.first_by_name_mut("Hydrogen").unwrap().neutron = 0;
+    /// assert_eq!(water[0].elem.neutron, 0);
+    /// ```
+    pub fn first_by_name_mut<Q>(&mut self, name: &Q) -> Option<&mut Element>
+    where
+        M: Lookup<Q, Vec<usize>>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        self.nth_by_name_mut(name, 0)
+    }
+
+    /// Returns a reference on the last element named `name`. If `name` is not found, `None` is returned.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// assert_eq!(water.last_by_name("Hydrogen").unwrap().neutron, 1);
+    /// assert!(water.last_by_name("Helium").is_none());
+    /// ```
+    pub fn last_by_name<Q>(&self, name: &Q) -> Option<&Element>
+    where
+        M: Lookup<Q, Vec<usize>>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let index = self.lookup_nth(name, 0, true)?;
+        Some(&self.list[index].elem)
+    }
+
+    /// Returns a mutable reference on the last element named `name`. If `name` is not found, `None` is returned.
+    ///
+    /// # Arguments
+    /// * `name` - Element's name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    ///
+    /// water.last_by_name_mut("Hydrogen").unwrap().neutron = 1;
+    /// assert_eq!(water[1].elem.neutron, 1);
+    /// ```
+    pub fn last_by_name_mut<Q>(&mut self, name: &Q) -> Option<&mut Element>
+    where
+        M: Lookup<Q, Vec<usize>>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let index = self.lookup_nth(name, 0, true)?;
+        Some(self.elem_mut(index))
+    }
+
+    /// Creates a new empty collection of named elements, where each element gets a unique alias built by
+    /// `format` from its name and a number. See the `alias` module.
+    ///
+    /// # Arguments
+    /// * `format` - Alias format
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::with_alias_format(|name, n| format!("{}/{}", name, n));
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    /// assert_eq!(water[1].alias.as_ref().unwrap(), "Hydrogen/1");
+    /// ```
+    pub fn with_alias_format(format: AliasFormat<K>) -> Self
+    where
+        M: Default,
+        N: Default,
+    {
+        let mut collection = NamedElementsCollection::new();
+        collection.set_alias_format(format);
+        collection
+    }
+
+    /// Sets the format of the aliases given to new elements. If the collection had no alias format yet,
+    /// elements already in the collection get their aliases, numbered in collection order for each name.
+    /// Otherwise existing aliases are kept.
+    ///
+    /// # Arguments
+    /// * `format` - Alias format
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::alias::hash_suffix;
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::new();
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    /// assert!(water[0].alias.is_none());
+    ///
+    /// water.set_alias_format(hash_suffix);
+    /// assert_eq!(water.get_by_alias("Hydrogen#1").unwrap().neutron, 1);
+    /// assert_eq!(water[1].alias.as_ref().unwrap(), "Oxygen#0");
+    /// ```
+    pub fn set_alias_format(&mut self, format: AliasFormat<K>)
+    where
+        M: Default,
+    {
+        let had_format = self.alias_format().is_some();
+        self.aliases_mut().format = Some(format);
+        if had_format {
+            return;
+        }
+
+        for i in 0..self.list.len() {
+            if self.handles.is_dead(i) {
+                continue;
+            }
+            // rank of the element among those sharing its name
+            let rank = self
+                .hmap
+                .get_indexes(&self.normalizer.normalize(&self.list[i].name))
+                .map_or(0, |v| v.as_slice().partition_point(|&j| j < i));
+            let alias = self.next_alias(&self.list[i].name, rank, None).unwrap();
+
+            {
+                let key = self.normalizer.normalize(&alias);
+                self.aliases_mut().map.add_element(&key, i);
+            }
+            self.list[i].alias = Some(alias);
+        }
+    }
+
+    /// Returns a reference on the element aliased `alias`. If `alias` is not found, `None` is returned.
+    ///
+    /// # Arguments
+    /// * `alias` - Element's alias
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::alias::hash_suffix;
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::with_alias_format(hash_suffix);
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// assert_eq!(water.get_by_alias("Hydrogen#0").unwrap().neutron, 0);
+    /// assert_eq!(water.get_by_alias("Hydrogen#1").unwrap().neutron, 1);
+    /// ```
+    pub fn get_by_alias<Q>(&self, alias: &Q) -> Option<&Element>
+    where
+        M: Lookup<Q, Vec<usize>>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let index = self.index_of_alias(alias)?;
+        Some(&self.list[index].elem)
+    }
+
+    /// Returns a mutable reference on the element aliased `alias`. If `alias` is not found, `None` is returned.
+    ///
+    /// # Arguments
+    /// * `alias` - Element's alias
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::alias::hash_suffix;
+    /// use nec::nec::DNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut water = DNEC::<Atom>::with_alias_format(hash_suffix);
+    ///
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    ///
+    /// water.get_by_alias_mut("Hydrogen#1").unwrap().neutron = 1;
+    /// assert_eq!(water.last_by_name("Hydrogen").unwrap().neutron, 1);
+    /// ```
+    pub fn get_by_alias_mut<Q>(&mut self, alias: &Q) -> Option<&mut Element>
+    where
+        M: Lookup<Q, Vec<usize>>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let index = self.index_of_alias(alias)?;
+        Some(self.elem_mut(index))
+    }
+
+    /// Removes the element aliased `alias` from the collection and returns it. If `alias` is not found,
+    /// `None` is returned.
+    ///
+    /// # Arguments
+    /// * `alias` - Element's alias
     ///
     /// # Examples
     ///
     /// ```
-    /// use nec::nec::UNEC;
+    /// use nec::alias::hash_suffix;
+    /// use nec::nec::DNEC;
     ///
     /// struct Atom { proton: u8, neutron: u8, };
-    /// let mut molecule = UNEC::<Atom>::new();
+    /// let mut water = DNEC::<Atom>::with_alias_format(hash_suffix);
     ///
-    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
-    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
-    /// assert_eq!(molecule.get_name(1).unwrap(), "Helium");
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+    ///
+    /// assert_eq!(water.remove_by_alias("Hydrogen#0").unwrap().elem.neutron, 0);
+    /// assert!(water.remove_by_alias("Hydrogen#0").is_none());
+    /// assert_eq!(water.get_by_alias("Hydrogen#1").unwrap().neutron, 1);
     /// ```
-    pub fn get_name(&self, index: usize) -> Option<&String> {
-        match self.list.get(index) {
-            Some(v) => Some(&v.name),
-            None => None,
-        }
+    pub fn remove_by_alias<Q>(&mut self, alias: &Q) -> Option<ElementBundle<Element, K>>
+    where
+        M: Lookup<Q, Vec<usize>>,
+        Q: ToOwned + ?Sized,
+        N: Normalizer<Q>,
+    {
+        let index = self.index_of_alias(alias)?;
+        Some(self.remove(index))
     }
+}
 
-    /// Adds an item at the end of the collection.
-    ///
-    /// # Arguments
-    /// * `name` - Element's name
-    /// * `element` - Element structure
+impl<Element, Indexes, K, N> NamedElementsCollection<Element, Indexes, K, BTreeMap<K, Indexes>, N>
+where
+    BTreeMap<K, Indexes>: NameMap<K, Indexes>,
+    K: Ord + Clone,
+    N: Normalizer<K>,
+    Indexes: Indexable,
+{
+    /// Returns an iterator over the element bundles in names order. Elements sharing the same name
+    /// are yielded in their collection order.
     ///
     /// # Examples
     ///
     /// ```
-    /// use nec::nec::UNEC;
+    /// use nec::nec::SortedDNEC;
     ///
     /// struct Atom { proton: u8, neutron: u8, };
-    /// let mut molecule = UNEC::<Atom>::new();
-    ///
-    /// for i in 0..10_u8 {
-    ///     molecule.push(&format!("Atom{}",i), Atom{ proton:i, neutron:i });
-    /// }
+    /// let mut water = SortedDNEC::<Atom>::new();
     ///
-    /// for i in 0..10_u8 {
-    ///     molecule.push(&format!("Atom{}",i), Atom{ proton:i, neutron:i });
-    /// }
-    /// assert_eq!(molecule.len(), 10);
+    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// water.push("Hydrogen", Atom{ proton:1, neutron:1 });
     ///
-    /// for i in 0..10_u8 {
-    ///     assert_eq!(molecule[i as usize].elem.proton, i);
-    /// }
-    pub fn push(&mut self, name: &str, element: Element) {
-        match self.hmap.already_in(name) {
-            // if name is already in our list, just replace the element
-            Some(index) => {
-                // and replace the element struct
-                self.list[index] = ElementBundle {
-                    elem: element,
-                    name: String::from(name),
-                };
-
-                // and also replace in hmap
-                self.hmap.replace_element(name, index);
-            }
-            // if not, just add the element
-            None => {
-                // add element
-                self.list.push(ElementBundle {
-                    elem: element,
-                    name: String::from(name),
-                });
-
-                // add index in the hash
-                let index = self.list.len() - 1;
-                self.hmap.add_element(name, index);
-            }
+    /// let names: Vec<_> = water.iter_sorted().map(|e| (e.name.as_str(), e.elem.neutron)).collect();
+    /// assert_eq!(names, vec![("Hydrogen", 0), ("Hydrogen", 1), ("Oxygen", 8)]);
+    /// ```
+    pub fn iter_sorted(&self) -> NecSorted<'_, Element, Indexes, K> {
+        NecSorted {
+            list: &self.list,
+            range: self.hmap.range::<K, _>(..),
+            indexes: [].iter(),
         }
     }
 
-
-    /// Removes an element from the collection by providing its index.
+    /// Returns an iterator over the element bundles whose names are in `range`, in names order.
     ///
     /// # Arguments
-    /// * `index` - Element index.
+    /// * `range` - Range of names
     ///
     /// # Panics
     ///
-    /// Panics if `index` is out of bounds.
+    /// Panics if the range start is greater than its end, or if both are equal and excluded, like
+    /// `BTreeMap::range()` does.
     ///
     /// # Examples
     ///
     /// ```
-    /// use nec::nec::UNEC;
+    /// use nec::nec::SortedUNEC;
     ///
     /// struct Atom { proton: u8, neutron: u8, };
-    /// let mut molecule = UNEC::<Atom>::new();
+    /// let mut molecule = SortedUNEC::<Atom>::new();
     ///
-    /// for i in 0..10 {
-    ///     molecule.push(&format!("Atom{}",i), Atom{ proton:i, neutron:i });
-    /// }
+    /// molecule.push("Lithium", Atom{ proton:3, neutron:4 });
+    /// molecule.push("Helium", Atom{ proton:2, neutron:2 });
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.push("Carbon", Atom{ proton:6, neutron:6 });
     ///
-    /// molecule.remove(5);
-    /// assert_eq!(molecule.len(),9);
-    /// assert_eq!(molecule.get_name(0).unwrap(),"Atom0");
-    /// assert_eq!(molecule.get_name(8).unwrap(),"Atom9");
+    /// let names: Vec<_> = molecule.range_by_name("D".."I").map(|e| e.name.as_str()).collect();
+    /// assert_eq!(names, vec!["Helium", "Hydrogen"]);
+    /// assert_eq!(molecule.range_by_name("Helium"..).count(), 3);
     /// ```
-    pub fn remove(&mut self, index: usize) -> ElementBundle<Element> {
-        // delete from main list
-        let e = self.list.remove(index);
+    pub fn range_by_name<'q, Q, R>(&self, range: R) -> NecSorted<'_, Element, Indexes, K>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ToOwned + ?Sized + 'q,
+        R: RangeBounds<&'q Q>,
+        N: Normalizer<Q>,
+    {
+        let start = range
+            .start_bound()
+            .map(|name| self.normalizer.normalize(*name));
+        let end = range
+            .end_bound()
+            .map(|name| self.normalizer.normalize(*name));
 
-        // remove relevant indexes from hashmap
-        let name = self.list.get(index).unwrap().name.clone();
-        self.hmap.delete_element(&name, index);
-
-        e
-    }    
-}
+        NecSorted {
+            list: &self.list,
+            range: self.hmap.range::<Q, _>((
+                start.as_ref().map(|name| &**name),
+                end.as_ref().map(|name| &**name),
+            )),
+            indexes: [].iter(),
+        }
+    }
 
-//-----------------------------------------------------------------------
-// Specializations
-//-----------------------------------------------------------------------
-impl<Element> NamedElementsCollection<Element, Vec<usize>> {
-    /// Returns a vector of elements having the same name. If `name`is not found, `None`is returned.
+    /// Returns an iterator over the names starting with `prefix`, in names order. Names are yielded in
+    /// their normalized form.
     ///
     /// # Arguments
-    /// * `name` - Element's name
+    /// * `prefix` - Names prefix
     ///
     /// # Examples
     ///
     /// ```
-    /// use nec::nec::DNEC;
-    ///
-    /// struct Atom { proton: u8, neutron: u8, };
-    /// let mut water = DNEC::<Atom>::new();
+    /// use nec::nec::SortedDNEC;
     ///
-    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
-    /// water.push("Hydrogen", Atom{ proton:1, neutron:0 });
-    /// water.push("Oxygen", Atom{ proton:8, neutron:8 });
+    /// let mut config = SortedDNEC::<u16>::new();
     ///
-    /// let mut v = water.get_by_name("Hydrogen").unwrap();
+    /// config.push("server.port", 80);
+    /// config.push("client.port", 8080);
+    /// config.push("server.host", 1);
+    /// config.push("server.port", 443);
     ///
-    /// assert_eq!(v.len(), 2);
-    /// assert_eq!(v[0].proton, 1);
-    /// assert_eq!(v[0].neutron, 0);
-    /// assert_eq!(v[1].proton, 1);
-    /// assert_eq!(v[1].neutron, 0);
-    /// assert!(water.get_by_name("Helium").is_none());
+    /// let names: Vec<&String> = config.names_with_prefix("server.").collect();
+    /// assert_eq!(names, vec!["server.host", "server.port"]);
     /// ```
-    pub fn get_by_name(&self, name: &str) -> Option<Vec<&Element>> {
-        if !self.hmap.contains_key(name) {
-            return None;
-        }
+    pub fn names_with_prefix<'a>(&'a self, prefix: &'a str) -> NecNamesWithPrefix<'a, Indexes, K>
+    where
+        K: Borrow<str>,
+        N: Normalizer<str>,
+    {
+        let prefix = self.normalizer.normalize(prefix);
 
-        let indexes = self.hmap.get(name).unwrap();
-        let v: Vec<_> = indexes
-            .iter()
-            .map(|i| &self.list.get(*i).unwrap().elem)
-            .collect();
-
-        Some(v)
+        NecNamesWithPrefix {
+            range: self
+                .hmap
+                .range::<str, _>((Bound::Included(&*prefix), Bound::Unbounded)),
+            prefix,
+        }
     }
 }
 
 //-----------------------------------------------------------------------
 // Index trait
 //-----------------------------------------------------------------------
-impl<Element, Indexes> Index<usize> for NamedElementsCollection<Element, Indexes>
+impl<Element, Indexes, K, M, N> Index<usize> for NamedElementsCollection<Element, Indexes, K, M, N>
 where
-    HashMap<String, Indexes>: Adjustable,
+    M: NameMap<K, Indexes>,
+    K: Eq + Clone,
+    N: Normalizer<K>,
     Indexes: Indexable,
 {
-    type Output = ElementBundle<Element>;
+    type Output = ElementBundle<Element, K>;
 
-    /// Gets access to an element by providing its index in the collection.
+    /// Gets access to an element by providing its index in the collection. Indexes are positions in `list`,
+    /// tombstones included, like the ones given by `iter_indexed()`.
     ///
     /// # Arguments
     /// * `index` - Element index.
     ///
     /// # Panics
     ///
-    /// Panics if `index` is out of bounds.
+    /// Panics if `index` is out of bounds or is a tombstone.
     ///
     /// # Examples
     /// ```
@@ -499,19 +3950,23 @@ where
     }
 }
 
-impl<Element, Indexes> IndexMut<usize> for NamedElementsCollection<Element, Indexes>
+impl<Element, Indexes, K, M, N> IndexMut<usize>
+    for NamedElementsCollection<Element, Indexes, K, M, N>
 where
-    HashMap<String, Indexes>: Adjustable,
+    M: NameMap<K, Indexes>,
+    K: Eq + Clone,
+    N: Normalizer<K>,
     Indexes: Indexable,
 {
-    /// Gets access to an element by providing its index in the collection.
+    /// Gets access to an element by providing its index in the collection. Indexes are positions in `list`,
+    /// tombstones included, like the ones given by `iter_indexed()`.
     ///
     /// # Arguments
     /// * `index` - Element index.
     ///
     /// # Panics
     ///
-    /// Panics if `index` is out of bounds.
+    /// Panics if `index` is out of bounds or is a tombstone.
     ///
     /// # Examples
     /// ```
@@ -525,14 +3980,21 @@ where
     /// assert_eq!(molecule[1].elem.proton, 2);
     /// assert_eq!(molecule[1].elem.neutron, 2);
     /// ```
-    fn index_mut(&mut self, index: usize) -> &mut ElementBundle<Element> {
+    fn index_mut(&mut self, index: usize) -> &mut ElementBundle<Element, K> {
         // get reference on vector of items
         self.get_mut(index).unwrap()
     }
 }
 
-impl<'a, Element> Index<&'a str> for NamedElementsCollection<Element, usize> {
-    type Output = ElementBundle<Element>;
+impl<Element, K, Q, M, N> Index<&Q> for NamedElementsCollection<Element, usize, K, M, N>
+where
+    M: NameMap<K, usize> + Lookup<Q, usize>,
+    K: Eq + Clone,
+    Q: ToOwned + ?Sized,
+    N: Normalizer<Q>,
+    N: Normalizer<K>,
+{
+    type Output = ElementBundle<Element, K>;
 
     /// Gets access to an element by providing its name in the collection. This only works for
     /// non-duplicated named objets (UNEC) because this trait returns a reference and cannot work with
@@ -557,10 +4019,46 @@ impl<'a, Element> Index<&'a str> for NamedElementsCollection<Element, usize> {
     /// assert_eq!(molecule["Hydrogen"].elem.proton, 1);
     /// assert_eq!(molecule["Hydrogen"].elem.neutron, 0);
     /// ```
-    fn index(&self, name: &str) -> &Self::Output {
+    fn index(&self, name: &Q) -> &Self::Output {
         // get reference on vector of items
-        let index = *self.hmap.get(name).unwrap();
-        &self.list.get(index).unwrap()
+        let index = *self.lookup(name).unwrap();
+        self.list.get(index).unwrap()
+    }
+}
+
+//-----------------------------------------------------------------------
+// Guards
+//-----------------------------------------------------------------------
+
+/// Guard giving mutable access to an element, returned by `get_indexed_mut()`. Secondary indexes are updated
+/// with the element's new keys when it's dropped.
+pub struct IndexedMut<'a, Element: 'a, Indexes: 'a + Indexable, K: 'a, M: 'a, N: 'a> {
+    collection: &'a mut NamedElementsCollection<Element, Indexes, K, M, N>,
+    index: usize,
+}
+
+impl<'a, Element, Indexes: Indexable, K, M, N> Deref for IndexedMut<'a, Element, Indexes, K, M, N> {
+    type Target = Element;
+
+    fn deref(&self) -> &Element {
+        &self.collection.list[self.index].elem
+    }
+}
+
+impl<'a, Element, Indexes: Indexable, K, M, N> DerefMut
+    for IndexedMut<'a, Element, Indexes, K, M, N>
+{
+    fn deref_mut(&mut self) -> &mut Element {
+        &mut self.collection.list[self.index].elem
+    }
+}
+
+impl<'a, Element, Indexes: Indexable, K, M, N> Drop for IndexedMut<'a, Element, Indexes, K, M, N> {
+    fn drop(&mut self) {
+        let collection = &mut *self.collection;
+        collection
+            .secondary
+            .add(&collection.list[self.index].elem, self.index);
     }
 }
 
@@ -569,89 +4067,274 @@ impl<'a, Element> Index<&'a str> for NamedElementsCollection<Element, usize> {
 //-----------------------------------------------------------------------
 
 /// Structure helper for consuming iterator.
-pub struct NecIntoIterator<Element> {
-    iter: ::std::vec::IntoIter<ElementBundle<Element>>,
+pub struct NecIntoIterator<Element, K = String> {
+    iter: ::std::vec::IntoIter<ElementBundle<Element, K>>,
 }
 
-impl<Element, Indexes> IntoIterator for NamedElementsCollection<Element, Indexes>
+impl<Element, Indexes, K, M, N> IntoIterator for NamedElementsCollection<Element, Indexes, K, M, N>
 where
     Indexes: Indexable,
 {
-    type Item = ElementBundle<Element>;
-    type IntoIter = NecIntoIterator<Element>;
+    type Item = ElementBundle<Element, K>;
+    type IntoIter = NecIntoIterator<Element, K>;
 
     fn into_iter(self) -> Self::IntoIter {
+        // tombstones are dropped
+        let list = if self.handles.dead() == 0 {
+            self.list
+        } else {
+            let handles = self.handles;
+            self.list
+                .into_iter()
+                .enumerate()
+                .filter(|&(i, _)| !handles.is_dead(i))
+                .map(|(_, e)| e)
+                .collect()
+        };
+
         NecIntoIterator {
-            iter: self.list.into_iter(),
+            iter: list.into_iter(),
         }
     }
 }
 
-impl<Element> Iterator for NecIntoIterator<Element> {
-    type Item = ElementBundle<Element>;
+impl<Element, K> Iterator for NecIntoIterator<Element, K> {
+    type Item = ElementBundle<Element, K>;
     fn next(&mut self) -> Option<Self::Item> {
         self.iter.next()
     }
 }
 
 /// StruDebugcture helper for non-consuming iterator.
-pub struct NecIter<'a, Element: 'a> {
-    iter: Iter<'a, ElementBundle<Element>>,
+pub struct NecIter<'a, Element: 'a, K: 'a = String> {
+    iter: ::std::iter::Enumerate<Iter<'a, ElementBundle<Element, K>>>,
+    handles: &'a Handles,
 }
 
-impl<'a, Element, Indexes> IntoIterator for &'a NamedElementsCollection<Element, Indexes>
+impl<'a, Element, Indexes, K, M, N> IntoIterator
+    for &'a NamedElementsCollection<Element, Indexes, K, M, N>
 where
     Indexes: Indexable,
 {
-    type Item = &'a ElementBundle<Element>;
-    type IntoIter = NecIter<'a, Element>;
+    type Item = &'a ElementBundle<Element, K>;
+    type IntoIter = NecIter<'a, Element, K>;
 
     fn into_iter(self) -> Self::IntoIter {
         NecIter {
-            iter: self.list.iter(),
+            iter: self.list.iter().enumerate(),
+            handles: &self.handles,
         }
     }
 }
 
-impl<'a, Element> Iterator for NecIter<'a, Element> {
-    type Item = &'a ElementBundle<Element>;
+impl<'a, Element, K> Iterator for NecIter<'a, Element, K> {
+    type Item = &'a ElementBundle<Element, K>;
     fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next()
+        let handles = self.handles;
+        self.iter
+            .find(|&(i, _)| !handles.is_dead(i))
+            .map(|(_, e)| e)
+    }
+}
+
+/// Structure helper for iterating over elements with their indexes.
+pub struct NecIterIndexed<'a, Element: 'a, K: 'a = String> {
+    iter: ::std::iter::Enumerate<Iter<'a, ElementBundle<Element, K>>>,
+    handles: &'a Handles,
+}
+
+impl<'a, Element, K> Iterator for NecIterIndexed<'a, Element, K> {
+    type Item = (usize, &'a ElementBundle<Element, K>);
+    fn next(&mut self) -> Option<Self::Item> {
+        let handles = self.handles;
+        self.iter.find(|&(i, _)| !handles.is_dead(i))
     }
 }
 
 /// Structure helper for non-consuming mutable iterator.
-pub struct NecIterMut<'a, Element: 'a> {
-    iter: IterMut<'a, ElementBundle<Element>>,
+pub struct NecIterMut<'a, Element: 'a, K: 'a = String> {
+    iter: ::std::iter::Enumerate<IterMut<'a, ElementBundle<Element, K>>>,
+    handles: &'a Handles,
 }
 
-impl<'a, Element, Indexes> IntoIterator for &'a mut NamedElementsCollection<Element, Indexes>
+impl<'a, Element, Indexes, K, M, N> IntoIterator
+    for &'a mut NamedElementsCollection<Element, Indexes, K, M, N>
 where
     Indexes: Indexable,
 {
-    type Item = &'a mut ElementBundle<Element>;
-    type IntoIter = NecIterMut<'a, Element>;
+    type Item = &'a mut ElementBundle<Element, K>;
+    type IntoIter = NecIterMut<'a, Element, K>;
 
     fn into_iter(self) -> Self::IntoIter {
+        self.secondary.touch();
         NecIterMut {
-            iter: self.list.iter_mut(),
+            iter: self.list.iter_mut().enumerate(),
+            handles: &self.handles,
         }
     }
 }
 
-impl<'a, Element> Iterator for NecIterMut<'a, Element> {
-    type Item = &'a mut ElementBundle<Element>;
+impl<'a, Element, K> Iterator for NecIterMut<'a, Element, K> {
+    type Item = &'a mut ElementBundle<Element, K>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let handles = self.handles;
+        self.iter
+            .find(|&(i, _)| !handles.is_dead(i))
+            .map(|(_, e)| e)
+    }
+}
+
+/// Structure helper for iterating over elements' names.
+pub struct NecNames<'a, Indexes: 'a + Indexable, K: 'a = String, M = HashMap<K, Indexes>>
+where
+    M: 'a + NameMap<K, Indexes>,
+{
+    iter: M::Names<'a>,
+}
+
+impl<'a, Indexes: Indexable, K, M: NameMap<K, Indexes>> Iterator for NecNames<'a, Indexes, K, M> {
+    type Item = &'a K;
     fn next(&mut self) -> Option<Self::Item> {
         self.iter.next()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, Indexes: Indexable, K, M: NameMap<K, Indexes>> ExactSizeIterator
+    for NecNames<'a, Indexes, K, M>
+{
+}
+
+/// Structure helper for iterating over element bundles sharing the same name.
+pub struct NecBundlesByName<'a, Element: 'a, K: 'a = String> {
+    list: &'a [ElementBundle<Element, K>],
+    indexes: Cow<'a, [usize]>,
+    // positions in indexes, not yet yielded
+    positions: ::std::ops::Range<usize>,
+}
+
+impl<'a, Element, K> Iterator for NecBundlesByName<'a, Element, K> {
+    type Item = &'a ElementBundle<Element, K>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let list = self.list;
+        self.positions.next().map(|p| &list[self.indexes[p]])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.positions.size_hint()
+    }
+}
+
+impl<'a, Element, K> DoubleEndedIterator for NecBundlesByName<'a, Element, K> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let list = self.list;
+        self.positions.next_back().map(|p| &list[self.indexes[p]])
+    }
+}
+
+impl<'a, Element, K> ExactSizeIterator for NecBundlesByName<'a, Element, K> {}
+
+/// Structure helper for iterating over elements sharing the same name.
+pub struct NecByName<'a, Element: 'a, K: 'a = String> {
+    iter: NecBundlesByName<'a, Element, K>,
+}
+
+impl<'a, Element, K> Iterator for NecByName<'a, Element, K> {
+    type Item = &'a Element;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|e| &e.elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, Element, K> DoubleEndedIterator for NecByName<'a, Element, K> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|e| &e.elem)
+    }
+}
+
+impl<'a, Element, K> ExactSizeIterator for NecByName<'a, Element, K> {}
+
+/// Structure helper for iterating over elements whose names match a wildcard pattern.
+pub struct NecGlob<'a, Element: 'a, K: 'a, N: 'a> {
+    iter: ::std::iter::Enumerate<Iter<'a, ElementBundle<Element, K>>>,
+    handles: &'a Handles,
+    pattern: Cow<'a, str>,
+    normalizer: &'a N,
+}
+
+impl<'a, Element, K, N> Iterator for NecGlob<'a, Element, K, N>
+where
+    K: Borrow<str>,
+    N: Normalizer<str>,
+{
+    type Item = (usize, &'a ElementBundle<Element, K>);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (pattern, normalizer, handles) = (&self.pattern, self.normalizer, self.handles);
+        self.iter.find(|&(i, e)| {
+            !handles.is_dead(i) && glob::matches(pattern, &normalizer.normalize(e.name.borrow()))
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+/// Structure helper for iterating over element bundles in names order.
+pub struct NecSorted<'a, Element: 'a, Indexes: 'a, K: 'a = String> {
+    list: &'a [ElementBundle<Element, K>],
+    range: btree_map::Range<'a, K, Indexes>,
+    // indexes of the current name, not yet yielded
+    indexes: Iter<'a, usize>,
+}
+
+impl<'a, Element, Indexes: Indexable, K> Iterator for NecSorted<'a, Element, Indexes, K> {
+    type Item = &'a ElementBundle<Element, K>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(&i) = self.indexes.next() {
+                return Some(&self.list[i]);
+            }
+            let (_, indexes) = self.range.next()?;
+            self.indexes = indexes.as_slice().iter();
+        }
+    }
+}
+
+/// Structure helper for iterating over names sharing a prefix.
+pub struct NecNamesWithPrefix<'a, Indexes: 'a, K: 'a = String> {
+    range: btree_map::Range<'a, K, Indexes>,
+    prefix: Cow<'a, str>,
+}
+
+impl<'a, Indexes, K: Borrow<str>> Iterator for NecNamesWithPrefix<'a, Indexes, K> {
+    type Item = &'a K;
+    fn next(&mut self) -> Option<Self::Item> {
+        // names are sorted: the first one without the prefix ends the iteration
+        let (name, _) = self.range.next()?;
+        if name.borrow().starts_with(&*self.prefix) {
+            Some(name)
+        } else {
+            None
+        }
+    }
 }
 
 //-----------------------------------------------------------------------
 // Clone
 //-----------------------------------------------------------------------
-impl<Element: Clone, Indexes> Clone for NamedElementsCollection<Element, Indexes>
+impl<Element: Clone, Indexes, K, M, N> Clone for NamedElementsCollection<Element, Indexes, K, M, N>
 where
-    HashMap<String, Indexes>: Adjustable,
+    M: NameMap<K, Indexes> + Clone,
+    K: Eq + Clone,
+    N: Normalizer<K> + Clone,
     Indexes: Indexable,
 {
     /// Builds a collection clone from an original one.
@@ -672,23 +4355,28 @@ where
     /// assert_eq!(peroxyide.len(), 4);
     /// ```
     fn clone(&self) -> Self {
-        let mut cloned = NamedElementsCollection::<Element, Indexes>::new();
-
-        // copy other fields which can be potentially already set
-        for e in self {
-            cloned.push(&e.name, e.elem.clone());
+        NamedElementsCollection {
+            list: self.list.clone(),
+            hmap: self.hmap.clone(),
+            policy: self.policy.clone(),
+            normalizer: self.normalizer.clone(),
+            indexes: PhantomData,
+            aliases: self.aliases.clone(),
+            secondary: self.secondary.clone(),
+            handles: self.handles.clone(),
         }
-
-        cloned
     }
 }
 
 //-----------------------------------------------------------------------
 // From
 //-----------------------------------------------------------------------
-impl<Element, Indexes> From<Vec<(String, Element)>> for NamedElementsCollection<Element, Indexes>
+impl<Element, Indexes, K, M, N> From<Vec<(K, Element)>>
+    for NamedElementsCollection<Element, Indexes, K, M, N>
 where
-    HashMap<String, Indexes>: Adjustable,
+    M: NameMap<K, Indexes> + Default,
+    K: Eq + Clone,
+    N: Normalizer<K> + Default,
     Indexes: Indexable,
 {
     /// Builds a collection from a vector of `(String, Element)` tuples.
@@ -705,24 +4393,146 @@ where
     /// assert_eq!(molecule[1].elem.proton, 1);
     /// assert_eq!(molecule[9].elem.neutron, 9);
     /// ```
-    fn from(source: Vec<(String, Element)>) -> Self {
-        let mut collection = NamedElementsCollection::<Element, Indexes>::new();
+    fn from(source: Vec<(K, Element)>) -> Self {
+        let mut collection = NamedElementsCollection::from_parts(0, M::default(), N::default());
 
         for e in source {
-            collection.push(&e.0, e.1);
+            collection.push(e.0, e.1);
+        }
+
+        collection
+    }
+}
+
+impl<Element, Indexes, K, M, N> From<Vec<Element>>
+    for NamedElementsCollection<Element, Indexes, K, M, N>
+where
+    M: NameMap<K, Indexes> + Default,
+    K: Eq + Clone,
+    N: Normalizer<K> + Default,
+    Indexes: Indexable,
+    Element: Nameable,
+    K: for<'a> From<&'a str>,
+{
+    /// Builds a collection from a vector of elements implementing the `Nameable` trait.
+    ///
+    /// # Examples
+    /// ```
+    /// use nec::nec::UNEC;
+    /// use nec::nameable::Nameable;
+    ///
+    /// struct Atom { symbol: String, proton: u8, };
+    /// impl Nameable for Atom {
+    ///     fn name(&self) -> &str {
+    ///         &self.symbol
+    ///     }
+    /// }
+    ///
+    /// let v: Vec<_> = (0..10).map(|i| Atom{ symbol: format!("ATOM{}",i), proton:i }).collect();
+    ///
+    /// let molecule = UNEC::<Atom>::from(v);
+    /// assert_eq!(molecule["ATOM1"].elem.proton, 1);
+    /// assert_eq!(molecule[9].elem.proton, 9);
+    /// ```
+    fn from(source: Vec<Element>) -> Self {
+        source.into_iter().collect()
+    }
+}
+
+//-----------------------------------------------------------------------
+// FromIterator
+//-----------------------------------------------------------------------
+impl<Element, Indexes, K, M, N> FromIterator<Element>
+    for NamedElementsCollection<Element, Indexes, K, M, N>
+where
+    M: NameMap<K, Indexes> + Default,
+    K: Eq + Clone,
+    N: Normalizer<K> + Default,
+    Indexes: Indexable,
+    Element: Nameable,
+    K: for<'a> From<&'a str>,
+{
+    /// Builds a collection from an iterator over elements implementing the `Nameable` trait.
+    ///
+    /// # Examples
+    /// ```
+    /// use nec::nec::DNEC;
+    ///
+    /// let water: DNEC<(&str, u8)> = vec![("H", 1), ("H", 1), ("O", 8)].into_iter().collect();
+    /// assert_eq!(water.len(), 3);
+    /// assert_eq!(water.get_by_name("H").unwrap().len(), 2);
+    /// ```
+    fn from_iter<I: IntoIterator<Item = Element>>(iter: I) -> Self {
+        let mut collection = NamedElementsCollection::from_parts(0, M::default(), N::default());
+
+        for e in iter {
+            collection.push_named(e);
         }
 
         collection
     }
 }
 
+//-----------------------------------------------------------------------
+// Extend
+//-----------------------------------------------------------------------
+impl<Element, Indexes, K, M, N> Extend<(K, Element)>
+    for NamedElementsCollection<Element, Indexes, K, M, N>
+where
+    M: NameMap<K, Indexes> + Default,
+    K: Eq + Clone,
+    N: Normalizer<K>,
+    Indexes: Indexable,
+{
+    /// Extends a collection with the `(String, Element)` tuples of an iterator. For a UNEC,
+    /// elements whose names are already in the collection follow the duplicate policy, like `push()` does:
+    /// by default, they replace the existing ones in place, and so do repeated names of the iterator. The new
+    /// elements are indexed in a single pass once all are added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the duplicate policy is `Error` and a name is already in the collection, or repeated. No element
+    /// is added then.
+    ///
+    /// # Examples
+    /// ```
+    /// use nec::nec::UNEC;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let mut molecule = UNEC::<Atom>::new();
+    ///
+    /// molecule.push("Hydrogen", Atom{ proton:1, neutron:0 });
+    /// molecule.extend((0..3).map(|i| (format!("ATOM{}",i), Atom{ proton:i, neutron:i })));
+    /// assert_eq!(molecule.len(), 4);
+    /// assert_eq!(molecule["ATOM2"].elem.proton, 2);
+    /// ```
+    fn extend<I: IntoIterator<Item = (K, Element)>>(&mut self, iter: I) {
+        let list: Vec<_> = iter
+            .into_iter()
+            .map(|(name, elem)| ElementBundle {
+                elem,
+                name,
+                alias: None,
+                aliases: Vec::new(),
+            })
+            .collect();
+        let policy = self.policy.clone();
+        let plan = self
+            .plan_bundles(list.iter(), &policy)
+            .unwrap_or_else(|_| panic!("{}", DUPLICATE_PANIC));
+        self.add_bundles(list, plan, policy);
+    }
+}
+
 //-----------------------------------------------------------------------
 // Debug
 //-----------------------------------------------------------------------
-impl<Element: fmt::Debug, Indexes: fmt::Debug> fmt::Debug
-    for NamedElementsCollection<Element, Indexes>
+impl<Element: fmt::Debug, Indexes, K: fmt::Debug, M: fmt::Debug, N> fmt::Debug
+    for NamedElementsCollection<Element, Indexes, K, M, N>
 where
-    HashMap<String, Indexes>: Adjustable,
+    M: NameMap<K, Indexes>,
+    K: Eq + Clone,
+    N: Normalizer<K>,
     Indexes: Indexable,
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -736,9 +4546,11 @@ where
 //-----------------------------------------------------------------------
 // Default
 //-----------------------------------------------------------------------
-impl<Element, Indexes> Default for NamedElementsCollection<Element, Indexes>
+impl<Element, Indexes, K, M, N> Default for NamedElementsCollection<Element, Indexes, K, M, N>
 where
-    HashMap<String, Indexes>: Adjustable,
+    M: NameMap<K, Indexes> + Default,
+    K: Eq + Clone,
+    N: Normalizer<K> + Default,
     Indexes: Indexable,
 {
     /// Creates an empty collection.
@@ -751,7 +4563,7 @@ where
     /// assert!(collection.is_empty());
     /// ```
     fn default() -> Self {
-        NamedElementsCollection::new()
+        NamedElementsCollection::from_parts(0, M::default(), N::default())
     }
 }
 
@@ -760,10 +4572,11 @@ where
 //-----------------------------------------------------------------------
 
 // type aliases
-type UniqueNamedElementsCollection<Element> = NamedElementsCollection<Element, usize>;
+type UniqueNamedElementsCollection<Element, K, M, N> =
+    NamedElementsCollection<Element, usize, K, M, N>;
 
 /// Named elements collection where no name duplication is possible. Adding an element with the same name
-/// just replaces the previous one.
+/// just replaces the previous one, unless another duplicate policy is set.
 ///
 /// # Examples
 /// ```
@@ -782,12 +4595,16 @@ type UniqueNamedElementsCollection<Element> = NamedElementsCollection<Element, u
 /// assert_eq!(molecule.len(), 1);
 /// assert_eq!(molecule.get(0).unwrap().elem.neutron, 1);
 /// ```
-pub type UNEC<Element> = UniqueNamedElementsCollection<Element>;
+pub type UNEC<Element, K = String, S = RandomState, N = Verbatim> =
+    UniqueNamedElementsCollection<Element, K, HashMap<K, usize, S>, N>;
 
-type DuplicateNamedElementsCollection<Element> = NamedElementsCollection<Element, Vec<usize>>;
+type DuplicateNamedElementsCollection<Element, K, M, N> =
+    NamedElementsCollection<Element, Vec<usize>, K, M, N>;
 
 /// Named elements collection where name duplication is allowed. Adding an element with the same name
-/// just adds an element.
+/// just adds an element. Indexes of the names are computed from an order statistics tree instead of being
+/// stored, so removing or inserting an element doesn't visit the indexes of all the others: see the `offset`
+/// module.
 ///
 /// # Examples
 /// ```
@@ -802,4 +4619,41 @@ type DuplicateNamedElementsCollection<Element> = NamedElementsCollection<Element
 /// molecule.push("Hydrogen", Atom { proton: 1, neutron: 1, });
 /// assert_eq!(molecule.len(), 4);
 /// ```
-pub type DNEC<Element> = DuplicateNamedElementsCollection<Element>;
+pub type DNEC<Element, K = String, S = RandomState, N = Verbatim> =
+    DuplicateNamedElementsCollection<Element, K, OffsetMap<K, S>, N>;
+
+/// Named elements collection where no name duplication is possible, and names are kept sorted. Besides the
+/// `UNEC` features, elements can be iterated in names order, and looked up by range of names.
+///
+/// # Examples
+/// ```
+/// use nec::nec::SortedUNEC;
+///
+/// struct Atom { proton: u8, neutron: u8, };
+/// let mut molecule = SortedUNEC::<Atom>::new();
+///
+/// molecule.push("Oxygen", Atom { proton: 8, neutron: 8, });
+/// molecule.push("Hydrogen", Atom { proton: 1, neutron: 0, });
+/// assert_eq!(molecule.get(0).unwrap().elem.proton, 8);
+/// assert_eq!(molecule.iter_sorted().next().unwrap().elem.proton, 1);
+/// ```
+pub type SortedUNEC<Element, K = String, N = Verbatim> =
+    UniqueNamedElementsCollection<Element, K, BTreeMap<K, usize>, N>;
+
+/// Named elements collection where name duplication is allowed, and names are kept sorted. Besides the
+/// `DNEC` features, elements can be iterated in names order, and looked up by range of names.
+///
+/// # Examples
+/// ```
+/// use nec::nec::SortedDNEC;
+///
+/// struct Atom { proton: u8, neutron: u8, };
+/// let mut water = SortedDNEC::<Atom>::new();
+///
+/// water.push("Oxygen", Atom { proton: 8, neutron: 8, });
+/// water.push("Hydrogen", Atom { proton: 1, neutron: 0, });
+/// water.push("Hydrogen", Atom { proton: 1, neutron: 0, });
+/// assert_eq!(water.iter_names().collect::<Vec<_>>(), vec!["Hydrogen", "Oxygen"]);
+/// ```
+pub type SortedDNEC<Element, K = String, N = Verbatim> =
+    DuplicateNamedElementsCollection<Element, K, BTreeMap<K, Vec<usize>>, N>;
@@ -0,0 +1,144 @@
+//! Normalizers applied to names when a collection stores them into its hashmap, and when it looks them up.
+//! Two names having the same normalized form are the same name for the collection, while the elements keep
+//! the spelling of their names as it was first given.
+//!
+//! # Examples
+//! ```
+//! use std::collections::hash_map::RandomState;
+//! use nec::nec::UNEC;
+//! use nec::normalizer::AsciiCaseFold;
+//!
+//! let mut headers = UNEC::<&str, String, RandomState, AsciiCaseFold>::new();
+//!
+//! headers.push("Content-Type", "text/plain");
+//! headers.push("content-type", "text/html");
+//!
+//! assert_eq!(headers.len(), 1);
+//! assert_eq!(headers["CONTENT-TYPE"].elem, "text/html");
+//! assert_eq!(headers.get_name(0).unwrap(), "Content-Type");
+//! ```
+use std::borrow::{Borrow, Cow};
+
+/// Turns a name into its normalized form.
+pub trait Normalizer<Q: ?Sized + ToOwned> {
+    /// Returns the normalized form of `name`, borrowing it when it's already normalized.
+    fn normalize<'a>(&self, name: &'a Q) -> Cow<'a, Q>;
+}
+
+/// Keeps names as they are. This is the default normalizer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Verbatim;
+
+/// Removes leading and trailing whitespaces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Trim;
+
+/// Maps ASCII uppercase letters to lowercase ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AsciiCaseFold;
+
+/// Maps `_` to `-`, so both separators are the same.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FoldSeparators;
+
+impl<Q: ?Sized + ToOwned> Normalizer<Q> for Verbatim {
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::normalizer::{Normalizer, Verbatim};
+    ///
+    /// assert_eq!(Verbatim.normalize(" Content-Type "), " Content-Type ");
+    /// ```
+    fn normalize<'a>(&self, name: &'a Q) -> Cow<'a, Q> {
+        Cow::Borrowed(name)
+    }
+}
+
+impl Normalizer<str> for Trim {
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::normalizer::{Normalizer, Trim};
+    ///
+    /// assert_eq!(Trim.normalize(" Content-Type "), "Content-Type");
+    /// ```
+    fn normalize<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        Cow::Borrowed(name.trim())
+    }
+}
+
+impl Normalizer<str> for AsciiCaseFold {
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::normalizer::{AsciiCaseFold, Normalizer};
+    ///
+    /// assert_eq!(AsciiCaseFold.normalize("Content-Type"), "content-type");
+    /// ```
+    fn normalize<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        if name.bytes().any(|b| b.is_ascii_uppercase()) {
+            Cow::Owned(name.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(name)
+        }
+    }
+}
+
+impl Normalizer<str> for FoldSeparators {
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::normalizer::{FoldSeparators, Normalizer};
+    ///
+    /// assert_eq!(FoldSeparators.normalize("content_type"), "content-type");
+    /// ```
+    fn normalize<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        if name.contains('_') {
+            Cow::Owned(name.replace('_', "-"))
+        } else {
+            Cow::Borrowed(name)
+        }
+    }
+}
+
+// a normalizer of str also normalizes String names
+macro_rules! impl_string_normalizer {
+    ($normalizer:ty) => {
+        impl Normalizer<String> for $normalizer {
+            fn normalize<'a>(&self, name: &'a String) -> Cow<'a, String> {
+                match Normalizer::<str>::normalize(self, name.as_str()) {
+                    // a borrowed slice of the same length is the name itself
+                    Cow::Borrowed(s) if s.len() == name.len() => Cow::Borrowed(name),
+                    s => Cow::Owned(s.into_owned()),
+                }
+            }
+        }
+    };
+}
+
+impl_string_normalizer!(Trim);
+impl_string_normalizer!(AsciiCaseFold);
+impl_string_normalizer!(FoldSeparators);
+
+/// Chains two normalizers: the first one is applied, then the second one.
+impl<Q, A, B> Normalizer<Q> for (A, B)
+where
+    Q: ?Sized + ToOwned,
+    A: Normalizer<Q>,
+    B: Normalizer<Q>,
+{
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::normalizer::{AsciiCaseFold, FoldSeparators, Normalizer, Trim};
+    ///
+    /// let normalizer = (Trim, (AsciiCaseFold, FoldSeparators));
+    /// assert_eq!(normalizer.normalize(" Content_Type "), "content-type");
+    /// ```
+    fn normalize<'a>(&self, name: &'a Q) -> Cow<'a, Q> {
+        match self.0.normalize(name) {
+            Cow::Borrowed(name) => self.1.normalize(name),
+            Cow::Owned(name) => Cow::Owned(self.1.normalize(name.borrow()).into_owned()),
+        }
+    }
+}
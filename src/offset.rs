@@ -0,0 +1,437 @@
+//! Map from names to indexes which doesn't store indexes, but computes them from an order statistics tree
+//! following the collection's list. Removing or inserting an element shifts the indexes of all the elements
+//! after it without visiting them, so these operations are logarithmic instead of proportional to the number
+//! of elements. The nodes of each name are kept in an order tree of their own, so the first, last or `n`-th
+//! index of a name is also found in logarithmic time. Only getting all the indexes of a name is proportional
+//! to their number, times the logarithm of the number of elements.
+//!
+//! It's used as the `hmap` index of `DNEC`. Moving the elements of the collection's list on removals and
+//! insertions is still proportional to the number of elements after them, but it's a single memory move.
+//!
+//! # Examples
+//! ```
+//! use nec::nec::DNEC;
+//!
+//! struct Atom { proton: u8, neutron: u8, };
+//! let mut water = DNEC::<Atom>::new();
+//!
+//! water.push("Hydrogen", Atom{ proton:1, neutron:0 });
+//! water.push("Oxygen", Atom{ proton:8, neutron:8 });
+//! water.push("Hydrogen", Atom{ proton:1, neutron:1 });
+//!
+//! water.remove(0);
+//! assert_eq!(water.first_by_name("Hydrogen").unwrap().neutron, 1);
+//! assert_eq!(water[0].name, "Oxygen");
+//! ```
+use std::borrow::{Borrow, Cow};
+use std::collections::hash_map::{self, RandomState};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+
+use adjustable::Adjustable;
+use map::{Lookup, NameMap};
+use order::OrderTree;
+
+/// Map from names to the nodes of an order tree, whose positions are the indexes of the elements. Names are
+/// hashed with `S`, like the keys of a `HashMap`.
+pub struct OffsetMap<K, S = RandomState> {
+    // nodes of each name, as the values of a tree sorted by positions. Their order never changes when other
+    // nodes are inserted or removed, so it's kept by inserting nodes at their positions
+    names: HashMap<K, OrderTree, S>,
+    // one node per element of the list, at least up to the last named one
+    tree: OrderTree,
+}
+
+// returns the indexes of the nodes of a name
+fn positions(tree: &OrderTree, nodes: &OrderTree) -> Vec<usize> {
+    nodes
+        .values()
+        .into_iter()
+        .map(|node| tree.rank(node))
+        .collect()
+}
+
+impl<K> OffsetMap<K> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        OffsetMap::with_hasher(RandomState::new())
+    }
+}
+
+impl<K, S> OffsetMap<K, S> {
+    /// Creates an empty map, using `hash_builder` to hash names.
+    ///
+    /// # Arguments
+    /// * `hash_builder` - Hasher builder used for the names
+    pub fn with_hasher(hash_builder: S) -> Self {
+        OffsetMap::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    /// Creates an empty map with room for at least `capacity` names without reallocating, using `hash_builder`
+    /// to hash names.
+    ///
+    /// # Arguments
+    /// * `capacity` - Number of names the map can hold without reallocating
+    /// * `hash_builder` - Hasher builder used for the names
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        OffsetMap {
+            names: HashMap::with_capacity_and_hasher(capacity, hash_builder),
+            tree: OrderTree::default(),
+        }
+    }
+
+    /// Returns a reference to the map's hasher builder.
+    pub fn hasher(&self) -> &S {
+        self.names.hasher()
+    }
+
+    /// Returns the number of names the map can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.names.capacity()
+    }
+
+    /// Returns the number of names in the map.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns `true` if the map has no name.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns an iterator over the names of the map, with their indexes in increasing order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::adjustable::Adjustable;
+    /// use nec::offset::OffsetMap;
+    ///
+    /// let mut h: OffsetMap<String> = OffsetMap::new();
+    ///
+    /// h.add_element(&"A".to_string(), 2);
+    /// h.add_element(&"A".to_string(), 0);
+    /// assert_eq!(h.iter().next(), Some((&"A".to_string(), vec![0,2])));
+    /// ```
+    pub fn iter(&self) -> OffsetIter<'_, K> {
+        OffsetIter {
+            iter: self.names.iter(),
+            tree: &self.tree,
+        }
+    }
+
+    // returns the node at index, adding nodes up to it if needed
+    fn node(&mut self, index: usize) -> usize {
+        while self.tree.len() <= index {
+            self.tree.push(0);
+        }
+        self.tree.select(index).unwrap()
+    }
+}
+
+impl<K, S> OffsetMap<K, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns the indexes of `name` in increasing order, or `None` if it's not in the map.
+    ///
+    /// # Arguments
+    /// * `name` - Name to look up
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::adjustable::Adjustable;
+    /// use nec::offset::OffsetMap;
+    ///
+    /// let mut h: OffsetMap<String> = OffsetMap::new();
+    ///
+    /// h.add_element(&"A".to_string(), 1);
+    /// h.insert_element(&"B".to_string(), 0);
+    /// assert_eq!(h.get("A"), Some(vec![2]));
+    /// assert!(h.get("C").is_none());
+    /// ```
+    pub fn get<Q>(&self, name: &Q) -> Option<Vec<usize>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.names.get(name).map(|v| positions(&self.tree, v))
+    }
+}
+
+impl<K: Hash + Eq + Clone, S: BuildHasher> OffsetMap<K, S> {
+    fn label(&mut self, name: &K, node: usize) {
+        let tree = &self.tree;
+        let rank = tree.rank(node);
+        let nodes = self.names.entry(name.clone()).or_default();
+        let pos = nodes.partition_point(|n| tree.rank(n) < rank);
+        nodes.insert(pos, node);
+    }
+
+    fn unlabel(&mut self, name: &K, node: usize) {
+        let tree = &self.tree;
+        let rank = tree.rank(node);
+        if let Some(nodes) = self.names.get_mut(name) {
+            let pos = nodes.partition_point(|n| tree.rank(n) < rank);
+            if let Some(n) = nodes.select(pos).filter(|&n| nodes.value(n) == node) {
+                nodes.remove(n);
+            }
+
+            // if not more indexes, remove key
+            if nodes.len() == 0 {
+                self.names.remove(name);
+            }
+        }
+    }
+}
+
+impl<K, S: Default> Default for OffsetMap<K, S> {
+    fn default() -> Self {
+        OffsetMap::with_hasher(S::default())
+    }
+}
+
+impl<K: Clone, S: Clone> Clone for OffsetMap<K, S> {
+    fn clone(&self) -> Self {
+        OffsetMap {
+            names: self.names.clone(),
+            tree: self.tree.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, S> fmt::Debug for OffsetMap<K, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, S> IntoIterator for &'a OffsetMap<K, S> {
+    type Item = (&'a K, Vec<usize>);
+    type IntoIter = OffsetIter<'a, K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Case of duplicated elements, whose indexes are computed.
+///
+/// Example:
+///
+/// list -> ("Elem1", e1), ("Elem2, e2"), ("Elem1", e1)
+/// tree -> n0, n1, n2
+/// names -> ("Elem1", tree of [n0,n2]), ("Elem2", tree of [n1])
+///
+impl<K, S> Adjustable<K> for OffsetMap<K, S>
+where
+    K: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::adjustable::Adjustable;
+    /// use nec::map::Lookup;
+    /// use nec::offset::OffsetMap;
+    ///
+    /// let mut h: OffsetMap<String> = OffsetMap::new();
+    ///
+    /// h.add_element(&"A".to_string(), 3);
+    /// h.add_element(&"A".to_string(), 1);
+    /// assert_eq!(*h.get_indexes("A").unwrap(), vec![1,3]);
+    /// ```
+    fn add_element(&mut self, name: &K, index: usize) {
+        let node = self.node(index);
+        self.label(name, node);
+    }
+
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::adjustable::Adjustable;
+    /// use nec::map::Lookup;
+    /// use nec::offset::OffsetMap;
+    ///
+    /// let mut h: OffsetMap<String> = OffsetMap::new();
+    ///
+    /// // 0 1 2 3 4
+    /// // A B A B A
+    /// for (i, name) in ["A", "B", "A", "B", "A"].iter().enumerate() {
+    ///     h.add_element(&name.to_string(), i);
+    /// }
+    ///
+    /// h.delete_element(&"A".to_string(), 2);
+    /// assert_eq!(*h.get_indexes("A").unwrap(), vec![0,3]);
+    /// assert_eq!(*h.get_indexes("B").unwrap(), vec![1,2]);
+    /// ```
+    fn delete_element(&mut self, name: &K, index: usize) {
+        let node = self.node(index);
+        self.unlabel(name, node);
+        self.tree.remove(node);
+    }
+
+    fn replace_element(&mut self, _name: &K, _index: usize) {}
+
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::adjustable::Adjustable;
+    /// use nec::map::Lookup;
+    /// use nec::offset::OffsetMap;
+    ///
+    /// let mut h: OffsetMap<String> = OffsetMap::new();
+    ///
+    /// h.add_element(&"A".to_string(), 0);
+    /// h.add_element(&"A".to_string(), 1);
+    ///
+    /// h.insert_element(&"B".to_string(), 1);
+    /// assert_eq!(*h.get_indexes("A").unwrap(), vec![0,2]);
+    /// assert_eq!(*h.get_indexes("B").unwrap(), vec![1]);
+    /// ```
+    fn insert_element(&mut self, name: &K, index: usize) {
+        if index > 0 {
+            self.node(index - 1);
+        }
+        let node = self.tree.insert(index, 0);
+        self.label(name, node);
+    }
+
+    fn already_in(&mut self, _name: &K) -> Option<usize> {
+        None
+    }
+
+    fn remove_index(&mut self, name: &K, index: usize) {
+        let node = self.node(index);
+        self.unlabel(name, node);
+    }
+
+    fn move_index(&mut self, name: &K, from: usize, to: usize) {
+        self.remove_index(name, from);
+        self.add_element(name, to);
+    }
+}
+
+impl<K, S> NameMap<K, Vec<usize>> for OffsetMap<K, S>
+where
+    K: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    type Names<'a>
+        = OffsetNames<'a, K>
+    where
+        Self: 'a,
+        K: 'a;
+
+    fn names(&self) -> Self::Names<'_> {
+        OffsetNames(self.names.keys())
+    }
+
+    fn shift(&mut self, index: usize, inserted: bool) {
+        if inserted {
+            if index <= self.tree.len() {
+                self.tree.insert(index, 0);
+            }
+        } else if let Some(node) = self.tree.select(index) {
+            self.tree.remove(node);
+        }
+    }
+
+    fn shift_removed(&mut self, removed: &[usize]) {
+        // from the end, so that the next indexes are still valid
+        for &index in removed.iter().rev() {
+            self.shift(index, false);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.names.clear();
+        self.tree.clear();
+    }
+}
+
+impl<K, S, Q> Lookup<Q, Vec<usize>> for OffsetMap<K, S>
+where
+    K: Hash + Eq + Borrow<Q>,
+    S: BuildHasher,
+    Q: Hash + Eq + ?Sized,
+{
+    fn get_indexes(&self, name: &Q) -> Option<Cow<'_, Vec<usize>>> {
+        self.get(name).map(Cow::Owned)
+    }
+
+    fn remove_indexes(&mut self, name: &Q) -> Option<Vec<usize>> {
+        // nodes are kept, as the elements are still in the list
+        let nodes = self.names.remove(name)?;
+        Some(positions(&self.tree, &nodes))
+    }
+
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::adjustable::Adjustable;
+    /// use nec::map::Lookup;
+    /// use nec::offset::OffsetMap;
+    ///
+    /// let mut h: OffsetMap<String> = OffsetMap::new();
+    ///
+    /// for i in 0..5 {
+    ///     h.add_element(&"A".to_string(), i * 2);
+    /// }
+    /// h.insert_element(&"B".to_string(), 0);
+    ///
+    /// assert_eq!(h.nth_index("A", 3), Some(7));
+    /// assert_eq!(h.nth_index("A", 5), None);
+    /// assert_eq!(h.count_indexes("A"), 5);
+    /// ```
+    fn nth_index(&self, name: &Q, n: usize) -> Option<usize> {
+        let nodes = self.names.get(name)?;
+        Some(self.tree.rank(nodes.value(nodes.select(n)?)))
+    }
+
+    fn count_indexes(&self, name: &Q) -> usize {
+        self.names.get(name).map_or(0, |nodes| nodes.len())
+    }
+}
+
+/// Iterator over the names of an `OffsetMap`, with their indexes.
+pub struct OffsetIter<'a, K: 'a> {
+    iter: hash_map::Iter<'a, K, OrderTree>,
+    tree: &'a OrderTree,
+}
+
+impl<'a, K> Iterator for OffsetIter<'a, K> {
+    type Item = (&'a K, Vec<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let tree = self.tree;
+        self.iter.next().map(|(k, v)| (k, positions(tree, v)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K> ExactSizeIterator for OffsetIter<'a, K> {}
+
+/// Iterator over the names of an `OffsetMap`.
+pub struct OffsetNames<'a, K: 'a>(hash_map::Keys<'a, K, OrderTree>);
+
+impl<'a, K> Iterator for OffsetNames<'a, K> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<&'a K> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K> ExactSizeIterator for OffsetNames<'a, K> {}
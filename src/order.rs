@@ -0,0 +1,284 @@
+// Order statistics tree, keeping a sequence of nodes in the order of the collection's list. A node's position
+// is not stored but computed from the subtree sizes, so inserting or removing a node implicitly shifts the
+// positions of all the nodes after it. All operations are logarithmic.
+//
+// This is a treap: nodes are ordered by position, and heap ordered by random priorities, which keeps the tree
+// balanced on average. Nodes are stored in an arena, and know their parent to compute their positions.
+
+// absent node
+const NIL: usize = usize::MAX;
+
+#[derive(Clone, Debug)]
+struct Node {
+    left: usize,
+    right: usize,
+    parent: usize,
+    // number of nodes in the subtree rooted at this node
+    size: usize,
+    priority: u64,
+    // data attached to the node
+    value: usize,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct OrderTree {
+    nodes: Vec<Node>,
+    // released nodes, to be reused
+    free: Vec<usize>,
+    root: usize,
+    // state of the priorities generator
+    seed: u64,
+}
+
+impl Default for OrderTree {
+    fn default() -> Self {
+        OrderTree {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: NIL,
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+}
+
+impl OrderTree {
+    // returns the number of nodes
+    pub(crate) fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.root = NIL;
+    }
+
+    pub(crate) fn value(&self, node: usize) -> usize {
+        self.nodes[node].value
+    }
+
+    // returns the node at position, if any
+    pub(crate) fn select(&self, mut position: usize) -> Option<usize> {
+        if position >= self.len() {
+            return None;
+        }
+
+        let mut node = self.root;
+        loop {
+            let left = self.size(self.nodes[node].left);
+            if position < left {
+                node = self.nodes[node].left;
+            } else if position == left {
+                return Some(node);
+            } else {
+                position -= left + 1;
+                node = self.nodes[node].right;
+            }
+        }
+    }
+
+    // returns the number of nodes at the start of the tree whose values satisfy pred, which must be true for
+    // all the nodes before the first one for which it's false
+    pub(crate) fn partition_point<F: Fn(usize) -> bool>(&self, pred: F) -> usize {
+        let mut position = 0;
+        let mut node = self.root;
+        while node != NIL {
+            if pred(self.nodes[node].value) {
+                position += self.size(self.nodes[node].left) + 1;
+                node = self.nodes[node].right;
+            } else {
+                node = self.nodes[node].left;
+            }
+        }
+        position
+    }
+
+    // returns the position of node
+    pub(crate) fn rank(&self, node: usize) -> usize {
+        let mut rank = self.size(self.nodes[node].left);
+        let mut child = node;
+        let mut parent = self.nodes[node].parent;
+
+        while parent != NIL {
+            if self.nodes[parent].right == child {
+                rank += self.size(self.nodes[parent].left) + 1;
+            }
+            child = parent;
+            parent = self.nodes[parent].parent;
+        }
+        rank
+    }
+
+    // adds a node at the end, and returns it
+    pub(crate) fn push(&mut self, value: usize) -> usize {
+        let len = self.len();
+        self.insert(len, value)
+    }
+
+    // inserts a node at position, shifting the next ones, and returns it
+    pub(crate) fn insert(&mut self, mut position: usize, value: usize) -> usize {
+        let node = self.alloc(value);
+        if self.root == NIL {
+            self.root = node;
+            return node;
+        }
+
+        // go down to the leaf where the node belongs, counting it in the sizes of its ancestors
+        let mut parent = self.root;
+        loop {
+            self.nodes[parent].size += 1;
+            let left = self.size(self.nodes[parent].left);
+            if position <= left {
+                if self.nodes[parent].left == NIL {
+                    self.nodes[parent].left = node;
+                    break;
+                }
+                parent = self.nodes[parent].left;
+            } else {
+                position -= left + 1;
+                if self.nodes[parent].right == NIL {
+                    self.nodes[parent].right = node;
+                    break;
+                }
+                parent = self.nodes[parent].right;
+            }
+        }
+        self.nodes[node].parent = parent;
+
+        // then up to where its priority belongs
+        while self.nodes[node].parent != NIL
+            && self.nodes[node].priority > self.nodes[self.nodes[node].parent].priority
+        {
+            self.rotate_up(node);
+        }
+        node
+    }
+
+    // removes node, shifting the next ones
+    pub(crate) fn remove(&mut self, node: usize) {
+        // go down until node is a leaf
+        loop {
+            let (left, right) = (self.nodes[node].left, self.nodes[node].right);
+            let child = match (left == NIL, right == NIL) {
+                (true, true) => break,
+                (false, true) => left,
+                (true, false) => right,
+                (false, false) => {
+                    if self.nodes[left].priority > self.nodes[right].priority {
+                        left
+                    } else {
+                        right
+                    }
+                }
+            };
+            self.rotate_up(child);
+        }
+
+        // then unlink it, and remove it from the sizes of its ancestors
+        let parent = self.nodes[node].parent;
+        if parent == NIL {
+            self.root = NIL;
+        } else if self.nodes[parent].left == node {
+            self.nodes[parent].left = NIL;
+        } else {
+            self.nodes[parent].right = NIL;
+        }
+
+        let mut ancestor = parent;
+        while ancestor != NIL {
+            self.nodes[ancestor].size -= 1;
+            ancestor = self.nodes[ancestor].parent;
+        }
+        self.free.push(node);
+    }
+
+    // returns the values of the nodes, in order
+    pub(crate) fn values(&self) -> Vec<usize> {
+        let mut values = Vec::with_capacity(self.len());
+        let mut stack = Vec::new();
+        let mut node = self.root;
+
+        while node != NIL || !stack.is_empty() {
+            while node != NIL {
+                stack.push(node);
+                node = self.nodes[node].left;
+            }
+            node = stack.pop().unwrap();
+            values.push(self.nodes[node].value);
+            node = self.nodes[node].right;
+        }
+        values
+    }
+
+    fn size(&self, node: usize) -> usize {
+        if node == NIL {
+            0
+        } else {
+            self.nodes[node].size
+        }
+    }
+
+    fn alloc(&mut self, value: usize) -> usize {
+        // xorshift
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+
+        let node = Node {
+            left: NIL,
+            right: NIL,
+            parent: NIL,
+            size: 1,
+            priority: self.seed,
+            value,
+        };
+        match self.free.pop() {
+            Some(i) => {
+                self.nodes[i] = node;
+                i
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    // moves node above its parent, keeping nodes order
+    fn rotate_up(&mut self, node: usize) {
+        let parent = self.nodes[node].parent;
+        let grandparent = self.nodes[parent].parent;
+
+        if self.nodes[parent].left == node {
+            let moved = self.nodes[node].right;
+            self.nodes[parent].left = moved;
+            if moved != NIL {
+                self.nodes[moved].parent = parent;
+            }
+            self.nodes[node].right = parent;
+        } else {
+            let moved = self.nodes[node].left;
+            self.nodes[parent].right = moved;
+            if moved != NIL {
+                self.nodes[moved].parent = parent;
+            }
+            self.nodes[node].left = parent;
+        }
+        self.nodes[parent].parent = node;
+        self.nodes[node].parent = grandparent;
+
+        if grandparent == NIL {
+            self.root = node;
+        } else if self.nodes[grandparent].left == parent {
+            self.nodes[grandparent].left = node;
+        } else {
+            self.nodes[grandparent].right = node;
+        }
+
+        // parent is now below node
+        self.nodes[parent].size =
+            1 + self.size(self.nodes[parent].left) + self.size(self.nodes[parent].right);
+        self.nodes[node].size =
+            1 + self.size(self.nodes[node].left) + self.size(self.nodes[node].right);
+    }
+}
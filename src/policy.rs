@@ -0,0 +1,98 @@
+//! Policies defining what happens when an element is added into a UNEC collection, while its
+//! name is already used by another element. DNEC collections accept duplicated names, so the
+//! policy is never used for them.
+use std::fmt;
+use std::sync::Arc;
+
+/// Closure of a `Merge` policy, merging the new element into the one already in the collection.
+pub type MergeFn<Element> = Arc<dyn Fn(&mut Element, Element) + Send + Sync>;
+
+/// Duplicate policy for UNEC collections. The default one is `KeepLast`.
+///
+/// # Examples
+/// ```
+/// use nec::nec::UNEC;
+/// use nec::policy::DuplicatePolicy;
+///
+/// struct Atom { proton: u8, neutron: u8, };
+/// let mut molecule = UNEC::<Atom>::with_duplicate_policy(DuplicatePolicy::KeepFirst);
+///
+/// molecule.push("Hydrogen", Atom { proton: 1, neutron: 0, });
+/// molecule.push("Hydrogen", Atom { proton: 1, neutron: 1, });
+/// assert_eq!(molecule["Hydrogen"].elem.neutron, 0);
+/// ```
+pub enum DuplicatePolicy<Element> {
+    /// The element already in the collection is kept, the new one is discarded.
+    KeepFirst,
+    /// The new element replaces the one already in the collection, at the same position.
+    KeepLast,
+    /// Adding the new element is an error.
+    Error,
+    /// The new element is merged into the one already in the collection by calling the closure. Use
+    /// `DuplicatePolicy::merge()` to build it.
+    Merge(MergeFn<Element>),
+}
+
+impl<Element> DuplicatePolicy<Element> {
+    /// Returns a `Merge` policy calling `f` with the element already in the collection and the new one.
+    ///
+    /// # Arguments
+    /// * `f` - Closure merging the new element into the existing one
+    ///
+    /// # Examples
+    /// ```
+    /// use nec::nec::UNEC;
+    /// use nec::policy::DuplicatePolicy;
+    ///
+    /// struct Atom { proton: u8, neutron: u8, };
+    /// let extra = 2;
+    /// let policy = DuplicatePolicy::merge(move |a: &mut Atom, b: Atom| a.neutron += b.neutron * extra);
+    /// let mut molecule = UNEC::<Atom>::with_duplicate_policy(policy);
+    ///
+    /// molecule.push("Hydrogen", Atom { proton: 1, neutron: 0, });
+    /// molecule.push("Hydrogen", Atom { proton: 1, neutron: 1, });
+    /// assert_eq!(molecule["Hydrogen"].elem.neutron, 2);
+    /// ```
+    pub fn merge<F>(f: F) -> Self
+    where
+        F: Fn(&mut Element, Element) + Send + Sync + 'static,
+    {
+        DuplicatePolicy::Merge(Arc::new(f))
+    }
+}
+
+// manual implementation, because deriving would require Element to implement Clone
+impl<Element> Clone for DuplicatePolicy<Element> {
+    fn clone(&self) -> Self {
+        match *self {
+            DuplicatePolicy::KeepFirst => DuplicatePolicy::KeepFirst,
+            DuplicatePolicy::KeepLast => DuplicatePolicy::KeepLast,
+            DuplicatePolicy::Error => DuplicatePolicy::Error,
+            DuplicatePolicy::Merge(ref f) => DuplicatePolicy::Merge(f.clone()),
+        }
+    }
+}
+
+impl<Element> fmt::Debug for DuplicatePolicy<Element> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DuplicatePolicy::KeepFirst => write!(f, "KeepFirst"),
+            DuplicatePolicy::KeepLast => write!(f, "KeepLast"),
+            DuplicatePolicy::Error => write!(f, "Error"),
+            DuplicatePolicy::Merge(_) => write!(f, "Merge"),
+        }
+    }
+}
+
+impl<Element> Default for DuplicatePolicy<Element> {
+    /// # Examples
+    ///
+    /// ```
+    /// use nec::policy::DuplicatePolicy;
+    ///
+    /// assert!(matches!(DuplicatePolicy::<u8>::default(), DuplicatePolicy::KeepLast));
+    /// ```
+    fn default() -> Self {
+        DuplicatePolicy::KeepLast
+    }
+}
@@ -0,0 +1,227 @@
+// Secondary indexes of a collection, mapping keys extracted from the elements to their indexes in the list.
+// Each index has its own key type, so they are kept behind a trait object and downcast back when looked up.
+use std::any::Any;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use error::NecError;
+
+// operations a collection applies on each of its secondary indexes, along with its own list changes
+pub(crate) trait SecondaryIndex<Element>: Send + Sync {
+    fn add(&mut self, elem: &Element, index: usize);
+    fn remove(&mut self, elem: &Element, index: usize);
+    fn shift(&mut self, index: usize, inserted: bool);
+    fn clear(&mut self);
+    fn box_clone(&self) -> Box<dyn SecondaryIndex<Element>>;
+    fn as_any(&self) -> &dyn Any;
+}
+
+// index of the elements by the key returned by `extract`. Indexes of each key are kept sorted
+pub(crate) struct KeyIndex<Element, Key> {
+    pub(crate) extract: Arc<dyn Fn(&Element) -> Key + Send + Sync>,
+    pub(crate) map: HashMap<Key, Vec<usize>>,
+}
+
+impl<Element, Key> SecondaryIndex<Element> for KeyIndex<Element, Key>
+where
+    Element: 'static,
+    Key: Hash + Eq + Clone + Send + Sync + 'static,
+{
+    fn add(&mut self, elem: &Element, index: usize) {
+        let v = self.map.entry((self.extract)(elem)).or_default();
+        let pos = v.binary_search(&index).unwrap_or_else(|p| p);
+        v.insert(pos, index);
+    }
+
+    fn remove(&mut self, elem: &Element, index: usize) {
+        let key = (self.extract)(elem);
+        if let Some(v) = self.map.get_mut(&key) {
+            if let Ok(pos) = v.binary_search(&index) {
+                v.remove(pos);
+            }
+
+            // if not more indexes, remove key
+            if v.is_empty() {
+                self.map.remove(&key);
+            }
+        }
+    }
+
+    fn shift(&mut self, index: usize, inserted: bool) {
+        for v in self.map.values_mut() {
+            for j in v {
+                if inserted && *j >= index {
+                    *j += 1;
+                } else if !inserted && *j > index {
+                    *j -= 1;
+                }
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    fn box_clone(&self) -> Box<dyn SecondaryIndex<Element>> {
+        Box::new(KeyIndex {
+            extract: self.extract.clone(),
+            map: self.map.clone(),
+        })
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// all secondary indexes of a collection, by names. When elements are mutably borrowed, indexes are flagged
+// as stale, and they are rebuilt once, before the collection changes its list again or by the next lookup.
+// They are kept behind a lock so that a lookup through a shared reference can rebuild them
+pub(crate) struct SecondaryIndexes<Element> {
+    state: Mutex<State<Element>>,
+}
+
+struct State<Element> {
+    indexes: Vec<(String, Box<dyn SecondaryIndex<Element>>)>,
+    stale: bool,
+}
+
+impl<Element> State<Element> {
+    fn rebuild<'a, I>(&mut self, elems: I)
+    where
+        Element: 'a,
+        I: Iterator<Item = (usize, &'a Element)>,
+    {
+        for &mut (_, ref mut i) in &mut self.indexes {
+            i.clear();
+        }
+        for (index, elem) in elems {
+            for &mut (_, ref mut i) in &mut self.indexes {
+                i.add(elem, index);
+            }
+        }
+        self.stale = false;
+    }
+}
+
+impl<Element> SecondaryIndexes<Element> {
+    pub(crate) fn new() -> Self {
+        SecondaryIndexes {
+            state: Mutex::new(State {
+                indexes: Vec::new(),
+                stale: false,
+            }),
+        }
+    }
+
+    // a panic while rebuilding leaves the indexes stale, so a poisoned lock is still usable
+    fn state(&mut self) -> &mut State<Element> {
+        self.state.get_mut().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<Element>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn is_stale(&mut self) -> bool {
+        self.state().stale
+    }
+
+    // adds the index named name, or replaces it
+    pub(crate) fn insert(&mut self, name: &str, index: Box<dyn SecondaryIndex<Element>>) {
+        let state = self.state();
+        match state.indexes.iter_mut().find(|&&mut (ref n, _)| n == name) {
+            Some(&mut (_, ref mut old)) => *old = index,
+            None => state.indexes.push((name.to_string(), index)),
+        }
+    }
+
+    // removes the index named name, and returns true if it existed
+    pub(crate) fn remove_index(&mut self, name: &str) -> bool {
+        let indexes = &mut self.state().indexes;
+        let len = indexes.len();
+        indexes.retain(|(n, _)| n != name);
+        len != indexes.len()
+    }
+
+    // returns the indexes of the elements whose key is key in the index named name, rebuilding the indexes
+    // first from elems if they are stale
+    pub(crate) fn lookup<'a, K, Key, I>(
+        &self,
+        name: &str,
+        key: &Key,
+        elems: I,
+    ) -> Result<Vec<usize>, NecError<K>>
+    where
+        Element: 'static,
+        Key: Hash + Eq + 'static,
+        I: Iterator<Item = (usize, &'a Element)>,
+    {
+        let mut state = self.lock();
+        if state.stale {
+            state.rebuild(elems);
+        }
+
+        let index = state
+            .indexes
+            .iter()
+            .find(|&(n, _)| n == name)
+            .ok_or_else(|| NecError::IndexNotFound(name.to_string()))?
+            .1
+            .as_any()
+            .downcast_ref::<KeyIndex<Element, Key>>()
+            .ok_or_else(|| NecError::WrongKeyType(name.to_string()))?;
+        Ok(index.map.get(key).cloned().unwrap_or_default())
+    }
+
+    // flags indexes as stale, if any
+    pub(crate) fn touch(&mut self) {
+        let state = self.state();
+        state.stale = !state.indexes.is_empty();
+    }
+
+    pub(crate) fn add(&mut self, elem: &Element, index: usize) {
+        for &mut (_, ref mut i) in &mut self.state().indexes {
+            i.add(elem, index);
+        }
+    }
+
+    pub(crate) fn remove(&mut self, elem: &Element, index: usize) {
+        for &mut (_, ref mut i) in &mut self.state().indexes {
+            i.remove(elem, index);
+        }
+    }
+
+    pub(crate) fn shift(&mut self, index: usize, inserted: bool) {
+        for &mut (_, ref mut i) in &mut self.state().indexes {
+            i.shift(index, inserted);
+        }
+    }
+
+    // rebuilds all indexes from the elements and their indexes, in a single pass
+    pub(crate) fn rebuild<'a, I>(&mut self, elems: I)
+    where
+        Element: 'a,
+        I: Iterator<Item = (usize, &'a Element)>,
+    {
+        self.state().rebuild(elems);
+    }
+}
+
+impl<Element> Clone for SecondaryIndexes<Element> {
+    fn clone(&self) -> Self {
+        let state = self.lock();
+        SecondaryIndexes {
+            state: Mutex::new(State {
+                indexes: state
+                    .indexes
+                    .iter()
+                    .map(|(n, i)| (n.clone(), i.box_clone()))
+                    .collect(),
+                stale: state.stale,
+            }),
+        }
+    }
+}
@@ -0,0 +1,477 @@
+//! Serde support, enabled by the `serde` feature. A UNEC is encoded as a map of names to elements, in the
+//! collection order. A DNEC is encoded as a list of `{name, elem}` pairs by default, which keeps the collection
+//! order, or as a map of names to arrays of elements when wrapped into `AsMap`, which only keeps the order of
+//! elements sharing the same name. Aliases are not encoded.
+//!
+//! Deserializing adds the elements to a new collection one by one, so its `hmap` index is rebuilt and the
+//! duplicate semantics apply: later elements replace earlier ones with the same name in a UNEC, while they
+//! are all kept in a DNEC.
+//!
+//! # Examples
+//! ```
+//! extern crate nec;
+//! extern crate serde_json;
+//!
+//! use nec::nec::{DNEC, UNEC};
+//! use nec::serialize::AsMap;
+//!
+//! let mut molecule = UNEC::<u8>::new();
+//! molecule.push("Oxygen", 8);
+//! molecule.push("Hydrogen", 1);
+//! assert_eq!(serde_json::to_string(&molecule).unwrap(), r#"{"Oxygen":8,"Hydrogen":1}"#);
+//!
+//! let mut water = DNEC::<u8>::new();
+//! water.push("Hydrogen", 1);
+//! water.push("Oxygen", 8);
+//! water.push("Hydrogen", 1);
+//! assert_eq!(
+//!     serde_json::to_string(&water).unwrap(),
+//!     r#"[{"name":"Hydrogen","elem":1},{"name":"Oxygen","elem":8},{"name":"Hydrogen","elem":1}]"#
+//! );
+//! assert_eq!(serde_json::to_string(&AsMap(&water)).unwrap(), r#"{"Hydrogen":[1,1],"Oxygen":[8]}"#);
+//!
+//! let water: AsMap<DNEC<u8>> = serde_json::from_str(r#"{"Hydrogen":[1,1],"Oxygen":[8]}"#).unwrap();
+//! assert_eq!(water.0.len(), 3);
+//! ```
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, SerializeStruct, Serializer};
+
+use map::NameMap;
+use nec::{ElementBundle, Indexable, NamedElementsCollection};
+use normalizer::Normalizer;
+
+/// Encodes a collection as a list of `{name, elem}` pairs, which is the default encoding of a DNEC. It's
+/// mostly useful for a UNEC, which is otherwise encoded as a map.
+///
+/// # Examples
+/// ```
+/// extern crate nec;
+/// extern crate serde_json;
+///
+/// use nec::nec::UNEC;
+/// use nec::serialize::AsList;
+///
+/// let mut molecule = UNEC::<u8>::new();
+/// molecule.push("Oxygen", 8);
+///
+/// let json = serde_json::to_string(&AsList(&molecule)).unwrap();
+/// assert_eq!(json, r#"[{"name":"Oxygen","elem":8}]"#);
+///
+/// let molecule: AsList<UNEC<u8>> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(molecule.0["Oxygen"].elem, 8);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AsList<C>(pub C);
+
+/// Encodes a DNEC as a map of names to arrays of elements. Names are ordered by their first element in the
+/// collection. With a normalizer, each spelling of a name gets its own entry, written next to the other
+/// spellings, so decoding gives the elements their names back.
+#[derive(Debug, Clone, Copy)]
+pub struct AsMap<C>(pub C);
+
+// collection where name duplication is allowed
+type Duplicates<Element, K, M, N> = NamedElementsCollection<Element, Vec<usize>, K, M, N>;
+
+/// Helper for encoding a collection field as a list of `{name, elem}` pairs, with
+/// `#[serde(with = "nec::serialize::as_list")]`.
+pub mod as_list {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::AsList;
+    use map::NameMap;
+    use nec::{Indexable, NamedElementsCollection};
+    use normalizer::Normalizer;
+
+    pub fn serialize<Element, Indexes, K, M, N, S>(
+        collection: &NamedElementsCollection<Element, Indexes, K, M, N>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        Element: Serialize,
+        Indexes: Indexable,
+        K: Serialize + Eq + Clone,
+        M: NameMap<K, Indexes>,
+        N: Normalizer<K>,
+        S: Serializer,
+    {
+        AsList(collection).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, Element, Indexes, K, M, N, D>(
+        deserializer: D,
+    ) -> Result<NamedElementsCollection<Element, Indexes, K, M, N>, D::Error>
+    where
+        Element: Deserialize<'de>,
+        Indexes: Indexable,
+        K: Deserialize<'de> + Eq + Clone,
+        M: NameMap<K, Indexes> + Default,
+        N: Normalizer<K> + Default,
+        D: Deserializer<'de>,
+    {
+        AsList::deserialize(deserializer).map(|c| c.0)
+    }
+}
+
+/// Helper for encoding a DNEC field as a map of names to arrays of elements, with
+/// `#[serde(with = "nec::serialize::as_map")]`.
+pub mod as_map {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{AsMap, Duplicates};
+    use map::NameMap;
+    use normalizer::Normalizer;
+
+    pub fn serialize<Element, K, M, N, S>(
+        collection: &Duplicates<Element, K, M, N>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        Element: Serialize,
+        K: Serialize + Eq + Clone,
+        M: NameMap<K, Vec<usize>>,
+        N: Normalizer<K>,
+        S: Serializer,
+    {
+        AsMap(collection).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, Element, K, M, N, D>(
+        deserializer: D,
+    ) -> Result<Duplicates<Element, K, M, N>, D::Error>
+    where
+        Element: Deserialize<'de>,
+        K: Deserialize<'de> + Eq + Clone,
+        M: NameMap<K, Vec<usize>> + Default,
+        N: Normalizer<K> + Default,
+        D: Deserializer<'de>,
+    {
+        AsMap::deserialize(deserializer).map(|c| c.0)
+    }
+}
+
+//-----------------------------------------------------------------------
+// Bundles
+//-----------------------------------------------------------------------
+
+impl<Element: Serialize, K: Serialize> Serialize for ElementBundle<Element, K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("ElementBundle", 2)?;
+        s.serialize_field("name", &self.name)?;
+        s.serialize_field("elem", &self.elem)?;
+        s.end()
+    }
+}
+
+const FIELDS: &[&str] = &["name", "elem"];
+
+// fields of an encoded bundle
+enum Field {
+    Name,
+    Elem,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+
+        impl<'de> Visitor<'de> for FieldVisitor {
+            type Value = Field;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("`name` or `elem`")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Field, E> {
+                match value {
+                    "name" => Ok(Field::Name),
+                    "elem" => Ok(Field::Elem),
+                    _ => Err(de::Error::unknown_field(value, FIELDS)),
+                }
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+struct BundleVisitor<Element, K>(PhantomData<fn() -> (Element, K)>);
+
+impl<'de, Element, K> Visitor<'de> for BundleVisitor<Element, K>
+where
+    Element: Deserialize<'de>,
+    K: Deserialize<'de>,
+{
+    type Value = ElementBundle<Element, K>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an element bundle")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let name = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let elem = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok(bundle(name, elem))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let (mut name, mut elem) = (None, None);
+        while let Some(field) = map.next_key()? {
+            match field {
+                Field::Name if name.is_some() => return Err(de::Error::duplicate_field("name")),
+                Field::Name => name = Some(map.next_value()?),
+                Field::Elem if elem.is_some() => return Err(de::Error::duplicate_field("elem")),
+                Field::Elem => elem = Some(map.next_value()?),
+            }
+        }
+        let name = name.ok_or_else(|| de::Error::missing_field("name"))?;
+        let elem = elem.ok_or_else(|| de::Error::missing_field("elem"))?;
+        Ok(bundle(name, elem))
+    }
+}
+
+// bundle decoded from a name and an element, without aliases
+fn bundle<Element, K>(name: K, elem: Element) -> ElementBundle<Element, K> {
+    ElementBundle {
+        elem,
+        name,
+        alias: None,
+        aliases: Vec::new(),
+    }
+}
+
+/// Decodes a bundle from its name and element. Its aliases are left empty.
+impl<'de, Element, K> Deserialize<'de> for ElementBundle<Element, K>
+where
+    Element: Deserialize<'de>,
+    K: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("ElementBundle", FIELDS, BundleVisitor(PhantomData))
+    }
+}
+
+//-----------------------------------------------------------------------
+// Collections
+//-----------------------------------------------------------------------
+
+/// Encodes a UNEC as a map of names to elements, in the collection order.
+impl<Element, K, M, N> Serialize for NamedElementsCollection<Element, usize, K, M, N>
+where
+    Element: Serialize,
+    K: Serialize + Eq + Clone,
+    M: NameMap<K, usize>,
+    N: Normalizer<K>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.live_len()))?;
+        for e in self.iter() {
+            map.serialize_entry(&e.name, &e.elem)?;
+        }
+        map.end()
+    }
+}
+
+/// Decodes a UNEC from a map of names to elements.
+impl<'de, Element, K, M, N> Deserialize<'de> for NamedElementsCollection<Element, usize, K, M, N>
+where
+    Element: Deserialize<'de>,
+    K: Deserialize<'de> + Eq + Clone,
+    M: NameMap<K, usize> + Default,
+    N: Normalizer<K> + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(MapVisitor::<Self>(PhantomData))
+    }
+}
+
+/// Encodes a DNEC as a list of `{name, elem}` pairs, in the collection order.
+impl<Element, K, M, N> Serialize for Duplicates<Element, K, M, N>
+where
+    Element: Serialize,
+    K: Serialize + Eq + Clone,
+    M: NameMap<K, Vec<usize>>,
+    N: Normalizer<K>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        AsList(self).serialize(serializer)
+    }
+}
+
+/// Decodes a DNEC from a list of `{name, elem}` pairs.
+impl<'de, Element, K, M, N> Deserialize<'de> for Duplicates<Element, K, M, N>
+where
+    Element: Deserialize<'de>,
+    K: Deserialize<'de> + Eq + Clone,
+    M: NameMap<K, Vec<usize>> + Default,
+    N: Normalizer<K> + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        AsList::deserialize(deserializer).map(|c| c.0)
+    }
+}
+
+impl<Element, Indexes, K, M, N> Serialize
+    for AsList<&NamedElementsCollection<Element, Indexes, K, M, N>>
+where
+    Element: Serialize,
+    Indexes: Indexable,
+    K: Serialize + Eq + Clone,
+    M: NameMap<K, Indexes>,
+    N: Normalizer<K>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.live_len()))?;
+        for e in self.0.iter() {
+            seq.serialize_element(e)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, Element, Indexes, K, M, N> Deserialize<'de>
+    for AsList<NamedElementsCollection<Element, Indexes, K, M, N>>
+where
+    Element: Deserialize<'de>,
+    Indexes: Indexable,
+    K: Deserialize<'de> + Eq + Clone,
+    M: NameMap<K, Indexes> + Default,
+    N: Normalizer<K> + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_seq(ListVisitor(PhantomData))
+            .map(AsList)
+    }
+}
+
+impl<Element, K, M, N> Serialize for AsMap<&Duplicates<Element, K, M, N>>
+where
+    Element: Serialize,
+    K: Serialize + Eq + Clone,
+    M: NameMap<K, Vec<usize>>,
+    N: Normalizer<K>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let collection = self.0;
+        let mut map = serializer.serialize_map(None)?;
+
+        // each name is encoded when its first element is met, with one entry per spelling when a normalizer
+        // makes several spellings the same name
+        for (i, e) in collection.iter_indexed() {
+            if collection.lookup_nth(&e.name, 0, false) != Some(i) {
+                continue;
+            }
+            let indexes = collection.lookup(&e.name).unwrap();
+            let mut spellings: Vec<(&K, Vec<&Element>)> = Vec::new();
+            for &j in indexes.iter() {
+                let bundle = &collection.list[j];
+                match spellings.iter_mut().find(|s| *s.0 == bundle.name) {
+                    Some(spelling) => spelling.1.push(&bundle.elem),
+                    None => spellings.push((&bundle.name, vec![&bundle.elem])),
+                }
+            }
+            for (name, elems) in spellings {
+                map.serialize_entry(name, &elems)?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl<'de, Element, K, M, N> Deserialize<'de> for AsMap<Duplicates<Element, K, M, N>>
+where
+    Element: Deserialize<'de>,
+    K: Deserialize<'de> + Eq + Clone,
+    M: NameMap<K, Vec<usize>> + Default,
+    N: Normalizer<K> + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_map(MapVisitor::<Duplicates<Element, K, M, N>>(PhantomData))
+            .map(AsMap)
+    }
+}
+
+// builds a collection from a list of bundles
+struct ListVisitor<C>(PhantomData<fn() -> C>);
+
+impl<'de, Element, Indexes, K, M, N> Visitor<'de>
+    for ListVisitor<NamedElementsCollection<Element, Indexes, K, M, N>>
+where
+    Element: Deserialize<'de>,
+    Indexes: Indexable,
+    K: Deserialize<'de> + Eq + Clone,
+    M: NameMap<K, Indexes> + Default,
+    N: Normalizer<K> + Default,
+{
+    type Value = NamedElementsCollection<Element, Indexes, K, M, N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list of element bundles")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut collection = NamedElementsCollection::new();
+        while let Some(bundle) = seq.next_element::<ElementBundle<Element, K>>()? {
+            collection.push(bundle.name, bundle.elem);
+        }
+        Ok(collection)
+    }
+}
+
+// builds a collection from a map of names to elements for a UNEC, or to arrays of elements for a DNEC
+struct MapVisitor<C>(PhantomData<fn() -> C>);
+
+impl<'de, Element, K, M, N> Visitor<'de>
+    for MapVisitor<NamedElementsCollection<Element, usize, K, M, N>>
+where
+    Element: Deserialize<'de>,
+    K: Deserialize<'de> + Eq + Clone,
+    M: NameMap<K, usize> + Default,
+    N: Normalizer<K> + Default,
+{
+    type Value = NamedElementsCollection<Element, usize, K, M, N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of names to elements")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut collection = NamedElementsCollection::new();
+        while let Some((name, elem)) = map.next_entry::<K, Element>()? {
+            collection.push(name, elem);
+        }
+        Ok(collection)
+    }
+}
+
+impl<'de, Element, K, M, N> Visitor<'de> for MapVisitor<Duplicates<Element, K, M, N>>
+where
+    Element: Deserialize<'de>,
+    K: Deserialize<'de> + Eq + Clone,
+    M: NameMap<K, Vec<usize>> + Default,
+    N: Normalizer<K> + Default,
+{
+    type Value = Duplicates<Element, K, M, N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of names to arrays of elements")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut collection = NamedElementsCollection::new();
+        while let Some((name, elems)) = map.next_entry::<K, Vec<Element>>()? {
+            for elem in elems {
+                collection.push(name.clone(), elem);
+            }
+        }
+        Ok(collection)
+    }
+}
@@ -0,0 +1,379 @@
+//! Reading and writing collections in a simple line-based text format, without any dependency. Each line is
+//! either empty, a comment starting with `#`, or a `name = value` pair:
+//!
+//! ```text
+//! # hydrogen isotopes
+//! Hydrogen = protium
+//! Hydrogen = "deuterium # heavy"
+//! "Oxy gen " = oxygen    # names can be quoted too
+//! ```
+//!
+//! Names and values are trimmed, and a `#` outside quotes starts a comment. Quoted names and values can contain
+//! any character, using the `\\`, `\"`, `\n`, `\r`, `\t`, `\0` and `\u{...}` escapes. Repeated names add
+//! several elements to a DNEC, while they're an error for a UNEC. Values are converted to elements with
+//! `FromStr`, and written back with `Display`, so elements are often simply strings.
+//!
+//! Pairs are read and written in the collection order, so writing a collection and reading it back gives
+//! the same collection.
+//!
+//! # Examples
+//! ```
+//! use nec::nec::DNEC;
+//! use nec::text;
+//!
+//! let input = "
+//! ## hydrogen isotopes
+//! Hydrogen = protium
+//! Hydrogen = \"deuterium # heavy\"
+//! Oxygen = oxygen    # most common
+//! ";
+//! let water: DNEC<String> = text::parse(input).unwrap();
+//! assert_eq!(water.get_by_name("Hydrogen").unwrap(), vec!["protium", "deuterium # heavy"]);
+//!
+//! let output = text::to_string(&water);
+//! assert_eq!(output, "Hydrogen = protium\nHydrogen = \"deuterium # heavy\"\nOxygen = oxygen\n");
+//! let water: DNEC<String> = text::parse(&output).unwrap();
+//! assert_eq!(text::to_string(&water), output);
+//! ```
+use std::fmt;
+use std::str::FromStr;
+
+use error::{NecError, ParseError, ParseErrorKind};
+use map::NameMap;
+use nec::{Indexable, NamedElementsCollection};
+use normalizer::Normalizer;
+
+/// Reads a new collection from `input`.
+///
+/// # Arguments
+/// * `input` - Text with one `name = value` pair per line
+///
+/// # Examples
+///
+/// ```
+/// use nec::error::ParseErrorKind;
+/// use nec::nec::UNEC;
+/// use nec::text;
+///
+/// let molecule: UNEC<u8> = text::parse("Hydrogen = 1\nHelium = 2").unwrap();
+/// assert_eq!(molecule["Helium"].elem, 2);
+///
+/// let result: Result<UNEC<u8>, _> = text::parse("Hydrogen = 1\n  Hydrogen = 2");
+/// let e = result.err().unwrap();
+/// assert_eq!((e.line, e.column), (2, 3));
+/// assert_eq!(e.kind, ParseErrorKind::DuplicateName("Hydrogen".to_string()));
+/// ```
+pub fn parse<Element, Indexes, M, N>(
+    input: &str,
+) -> Result<NamedElementsCollection<Element, Indexes, String, M, N>, ParseError>
+where
+    Element: FromStr,
+    Element::Err: fmt::Display,
+    Indexes: Indexable,
+    M: NameMap<String, Indexes> + Default,
+    N: Normalizer<String> + Default,
+{
+    let mut collection = NamedElementsCollection::new();
+    parse_into(&mut collection, input)?;
+    Ok(collection)
+}
+
+/// Reads `input` and adds its pairs at the end of `collection`, for instance to use the collection's
+/// normalizer. On error, the pairs before the faulty line are already added.
+///
+/// # Arguments
+/// * `collection` - Collection receiving the elements
+/// * `input` - Text with one `name = value` pair per line
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::hash_map::RandomState;
+/// use nec::nec::UNEC;
+/// use nec::normalizer::AsciiCaseFold;
+/// use nec::text;
+///
+/// let mut molecule = UNEC::<String, String, RandomState, _>::with_normalizer(AsciiCaseFold);
+/// text::parse_into(&mut molecule, "Hydrogen = H").unwrap();
+/// assert!(text::parse_into(&mut molecule, "HYDROGEN = H").is_err());
+/// ```
+pub fn parse_into<Element, Indexes, M, N>(
+    collection: &mut NamedElementsCollection<Element, Indexes, String, M, N>,
+    input: &str,
+) -> Result<(), ParseError>
+where
+    Element: FromStr,
+    Element::Err: fmt::Display,
+    Indexes: Indexable,
+    M: NameMap<String, Indexes>,
+    N: Normalizer<String>,
+{
+    for (i, line) in input.lines().enumerate() {
+        let mut cursor = Cursor {
+            chars: line.chars().collect(),
+            pos: 0,
+            line: i + 1,
+        };
+
+        // skip empty lines and comments
+        cursor.skip_spaces();
+        if cursor.at_end() {
+            continue;
+        }
+
+        let name_column = cursor.column();
+        let name = cursor.name()?;
+        cursor.skip_spaces();
+        let value_column = cursor.column();
+        let value = cursor.value()?;
+
+        let elem = value
+            .parse::<Element>()
+            .map_err(|e| cursor.error(value_column, ParseErrorKind::InvalidValue(e.to_string())))?;
+        if let Err(NecError::DuplicateName(name)) = collection.try_push(name, elem) {
+            return Err(cursor.error(name_column, ParseErrorKind::DuplicateName(name)));
+        }
+    }
+    Ok(())
+}
+
+/// Writes the collection to `out`, one `name = value` pair per line, in the collection order. Names and values
+/// are quoted when needed.
+///
+/// # Arguments
+/// * `collection` - Collection to write
+/// * `out` - Destination of the text
+///
+/// # Examples
+///
+/// ```
+/// use nec::nec::UNEC;
+/// use nec::text;
+///
+/// let mut molecule = UNEC::<String>::new();
+/// molecule.push("Hydrogen", "H".to_string());
+/// molecule.push("Oxygen ", "\"O\"".to_string());
+///
+/// let mut s = String::new();
+/// text::write(&molecule, &mut s).unwrap();
+/// assert_eq!(s, "Hydrogen = H\n\"Oxygen \" = \"\\\"O\\\"\"\n");
+/// ```
+pub fn write<Element, Indexes, M, N, W>(
+    collection: &NamedElementsCollection<Element, Indexes, String, M, N>,
+    out: &mut W,
+) -> fmt::Result
+where
+    Element: fmt::Display,
+    Indexes: Indexable,
+    M: NameMap<String, Indexes>,
+    N: Normalizer<String>,
+    W: fmt::Write,
+{
+    for e in collection.iter() {
+        write_token(out, &e.name, true)?;
+        out.write_str(" = ")?;
+        write_token(out, &e.elem.to_string(), false)?;
+        out.write_char('\n')?;
+    }
+    Ok(())
+}
+
+/// Returns the collection as text, like `write()` does.
+///
+/// # Arguments
+/// * `collection` - Collection to write
+///
+/// # Examples
+///
+/// ```
+/// use nec::nec::DNEC;
+/// use nec::text;
+///
+/// let mut water = DNEC::<u8>::new();
+/// water.push("Hydrogen", 1);
+/// water.push("Hydrogen", 1);
+/// assert_eq!(text::to_string(&water), "Hydrogen = 1\nHydrogen = 1\n");
+/// ```
+pub fn to_string<Element, Indexes, M, N>(
+    collection: &NamedElementsCollection<Element, Indexes, String, M, N>,
+) -> String
+where
+    Element: fmt::Display,
+    Indexes: Indexable,
+    M: NameMap<String, Indexes>,
+    N: Normalizer<String>,
+{
+    let mut s = String::new();
+    write(collection, &mut s).unwrap();
+    s
+}
+
+// writes s, quoted and escaped if it can't be read back as is
+fn write_token<W: fmt::Write>(out: &mut W, s: &str, name: bool) -> fmt::Result {
+    let quote = s.is_empty()
+        || s.starts_with(char::is_whitespace)
+        || s.ends_with(char::is_whitespace)
+        || s.chars()
+            .any(|c| c == '#' || c == '"' || c == '\\' || c.is_control() || (name && c == '='));
+    if !quote {
+        return out.write_str(s);
+    }
+
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '\\' => out.write_str("\\\\")?,
+            '"' => out.write_str("\\\"")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            '\0' => out.write_str("\\0")?,
+            c if c.is_control() => write!(out, "\\u{{{:x}}}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+// position in a line being read
+struct Cursor {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Cursor {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    // 1-based column of the current position
+    fn column(&self) -> usize {
+        self.pos + 1
+    }
+
+    fn error(&self, column: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            line: self.line,
+            column,
+            kind,
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    // true at the end of the line or at a comment
+    fn at_end(&self) -> bool {
+        matches!(self.peek(), None | Some('#'))
+    }
+
+    // reads a name up to '=', and the '='
+    fn name(&mut self) -> Result<String, ParseError> {
+        let column = self.column();
+        let name = if self.peek() == Some('"') {
+            let name = self.quoted()?;
+            self.skip_spaces();
+            name
+        } else {
+            let start = self.pos;
+            while self.peek().is_some_and(|c| c != '=' && c != '#') {
+                self.pos += 1;
+            }
+            let name: String = self.chars[start..self.pos].iter().collect();
+            let name = name.trim_end().to_string();
+            if name.is_empty() && self.peek() == Some('=') {
+                return Err(self.error(column, ParseErrorKind::EmptyName));
+            }
+            name
+        };
+
+        if self.peek() != Some('=') {
+            return Err(self.error(self.column(), ParseErrorKind::MissingEquals));
+        }
+        self.pos += 1;
+        Ok(name)
+    }
+
+    // reads a value up to the end of the line or a comment
+    fn value(&mut self) -> Result<String, ParseError> {
+        if self.peek() == Some('"') {
+            let value = self.quoted()?;
+            self.skip_spaces();
+            if !self.at_end() {
+                return Err(self.error(self.column(), ParseErrorKind::TrailingCharacters));
+            }
+            return Ok(value);
+        }
+
+        let start = self.pos;
+        while !self.at_end() {
+            self.pos += 1;
+        }
+        let value: String = self.chars[start..self.pos].iter().collect();
+        Ok(value.trim_end().to_string())
+    }
+
+    // reads a quoted string, unescaping it
+    fn quoted(&mut self) -> Result<String, ParseError> {
+        let column = self.column();
+        self.pos += 1;
+
+        let mut s = String::new();
+        loop {
+            match self.next() {
+                None => return Err(self.error(column, ParseErrorKind::UnterminatedQuote)),
+                Some('"') => return Ok(s),
+                Some('\\') => {
+                    let escape = self.pos - 1;
+                    let c = match self.next() {
+                        Some('\\') => Some('\\'),
+                        Some('"') => Some('"'),
+                        Some('n') => Some('\n'),
+                        Some('r') => Some('\r'),
+                        Some('t') => Some('\t'),
+                        Some('0') => Some('\0'),
+                        Some('u') => self.unicode(),
+                        _ => None,
+                    };
+                    match c {
+                        Some(c) => s.push(c),
+                        None => {
+                            let end = self.pos.min(self.chars.len());
+                            let sequence = self.chars[escape..end].iter().collect();
+                            return Err(
+                                self.error(escape + 1, ParseErrorKind::InvalidEscape(sequence))
+                            );
+                        }
+                    }
+                }
+                Some(c) => s.push(c),
+            }
+        }
+    }
+
+    // reads the `{...}` part of a `\u{...}` escape
+    fn unicode(&mut self) -> Option<char> {
+        if self.next() != Some('{') {
+            return None;
+        }
+        let (mut code, mut digits) = (0_u32, 0);
+        loop {
+            match self.next()? {
+                '}' if digits > 0 => return ::std::char::from_u32(code),
+                c if digits < 6 => code = code * 16 + c.to_digit(16)?,
+                _ => return None,
+            }
+            digits += 1;
+        }
+    }
+}